@@ -0,0 +1,218 @@
+/*!
+Encoding of Aurora calls and decoding of their output, for other contracts or off-chain code.
+
+```ignore
+use contract_bridge::abi;
+
+let input = abi::encode_call(
+    "approve(address,uint256)",
+    &["6eba6ef721bd532ca8e5d5ec8faf7f0fa0dac474", "1.5 ether"],
+)?;
+let args = abi::CallArgs::V2(abi::FunctionCallArgsV2 {
+    contract: abi::parse_address("A5C7FDbe2a8B814369e89EAed7EE52630fcb4C59")?,
+    value: Default::default(),
+    input,
+});
+```
+*/
+use crate::output;
+use crate::utils;
+use aurora_engine_types::types::RawU256;
+use near_sdk::serde_json::Value;
+
+pub use crate::aurora::{
+    CallArgs, FunctionCallArgsV2, RawAddress, ResultLog, SubmitResult, TransactionStatus,
+    ViewCallArgs,
+};
+pub use crate::error::{BridgeError, Result};
+pub use crate::output::{
+    CallOutput, CallReport, DeployOutput, LogOutput, MulticallOutput, MulticallResult, ViewOutput,
+};
+pub use crate::utils::Selector;
+
+/// Encodes the input of a call to `signature` (e.g. `"approve(address spender, uint amount)"`),
+/// with its values given as strings, e.g. `["0x...", "1.5 ether"]`.
+pub fn encode_call(signature: &str, values: &[impl AsRef<str>]) -> Result<Vec<u8>> {
+    utils::solidity_function(signature, values)
+}
+
+/// Same as `encode_call`, with JSON values, e.g. `[[1, 2], true, {"amount": "1", "to": "0x..."}]`.
+pub fn encode_call_json(signature: &str, values: &[Value]) -> Result<Vec<u8>> {
+    utils::solidity_function_json(signature, values)
+}
+
+/// Encodes the init code of a deployment, the hex `bytecode` followed by the arguments of
+/// `constructor` (e.g. `"constructor(string name, uint8 decimals)"`) if given.
+pub fn encode_deploy(
+    bytecode: &str,
+    constructor: Option<&str>,
+    values: &[impl AsRef<str>],
+) -> Result<Vec<u8>> {
+    utils::deploy_input(bytecode, constructor, values)
+}
+
+/// Selector of `signature`, hashed in its canonical form.
+pub fn selector(signature: &str) -> Result<Selector> {
+    utils::get_selector(signature)
+}
+
+/// Canonical form of `signature`, e.g. `approve(address,uint256)`.
+pub fn canonical_signature(signature: &str) -> Result<String> {
+    utils::canonical_signature(signature)
+}
+
+/// Parses a 20 bytes hex address, with or without the `0x` prefix.
+pub fn parse_address(address: &str) -> Result<RawAddress> {
+    Ok(utils::from_string_to_address(address)?.0)
+}
+
+/// Address of a NEAR account on Aurora, e.g. the address the bridge calls Aurora contracts from.
+pub fn near_account_to_address(account_id: &str) -> RawAddress {
+    utils::near_account_to_address(account_id)
+}
+
+/// Parses a 32 bytes hex value, such as a CREATE2 salt or an init code hash.
+pub fn parse_bytes32(value: &str) -> Result<[u8; 32]> {
+    utils::parse_bytes32(value)
+}
+
+/// Address of a contract deployed with CREATE2 by `factory` with `salt`, where
+/// `init_code_hash` is the keccak of the bytecode followed by the constructor arguments.
+pub fn create2_address(
+    factory: &RawAddress,
+    salt: &[u8; 32],
+    init_code_hash: &[u8; 32],
+) -> RawAddress {
+    utils::create2_address(factory, salt, init_code_hash)
+}
+
+/// Parses an amount of wei (e.g. `"1.5 ether"` or `"0x3e8"`) into the `value` of
+/// `FunctionCallArgsV2`.
+pub fn parse_wei(value: &str) -> Result<RawU256> {
+    utils::parse_wei(value)
+}
+
+/// Decodes an EVM output with its return signature (e.g. `"(bool,uint256[])"`) into JSON:
+/// decimal strings for integers and `0x` hex for addresses and bytes.
+pub fn decode_output(returns: &str, output: &[u8]) -> Result<Vec<Value>> {
+    output::decode_output(&output::return_types(returns)?, output)
+}
+
+/// Readable reason of a revert payload, for `Error(string)` and `Panic(uint256)` reverts.
+pub fn revert_reason(data: &[u8]) -> String {
+    output::revert_reason(data, |_| None)
+}
+
+/// Encodes a Multicall3 `aggregate3` call of `calls`, each given as its target and input.
+/// Unless `allow_failure` is set, a failed call reverts all of them.
+pub fn encode_aggregate3(calls: &[(RawAddress, Vec<u8>)], allow_failure: bool) -> Result<Vec<u8>> {
+    utils::aggregate3(calls, allow_failure)
+}
+
+/// Decodes the output of Multicall3's `aggregate3` into the result of each call.
+pub fn decode_aggregate3(output: &[u8]) -> Result<Vec<MulticallResult>> {
+    output::aggregate3_results(output, |_| None)
+}
+
+/// Reports the status, output and revert reason of an Aurora call result.
+pub fn call_report(result: SubmitResult) -> CallReport {
+    output::call_report(result, |_| None)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::serde_json::json;
+
+    #[test]
+    fn test_encode_call() {
+        let input = encode_call(
+            "approve(address spender, uint amount)",
+            &["6eba6ef721bd532ca8e5d5ec8faf7f0fa0dac474", "1"],
+        )
+        .unwrap();
+        assert_eq!(input[..4], selector("approve(address,uint256)").unwrap());
+        assert_eq!(
+            encode_call_json(
+                "approve(address,uint256)",
+                &[json!("6eba6ef721bd532ca8e5d5ec8faf7f0fa0dac474"), json!(1)]
+            )
+            .unwrap(),
+            input
+        );
+        assert_eq!(
+            decode_output("(address,uint256)", &input[4..]).unwrap(),
+            vec![
+                json!("0x6eba6ef721bd532ca8e5d5ec8faf7f0fa0dac474"),
+                json!("1")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_aggregate3() {
+        let target = parse_address("A5C7FDbe2a8B814369e89EAed7EE52630fcb4C59").unwrap();
+        let input = encode_aggregate3(&[(target, vec![0x01, 0x02])], false).unwrap();
+        assert_eq!(input[..4], [0x82, 0xad, 0x56, 0xcb]);
+        assert_eq!(
+            decode_output("((address,bool,bytes)[])", &input[4..]).unwrap(),
+            vec![json!([[
+                "0xa5c7fdbe2a8b814369e89eaed7ee52630fcb4c59",
+                false,
+                "0x0102"
+            ]])]
+        );
+
+        let error = [
+            vec![0x08, 0xc3, 0x79, 0xa0],
+            ethabi::encode(&[ethabi::Token::String("E1".to_string())]),
+        ]
+        .concat();
+        let output = ethabi::encode(&[ethabi::Token::Array(vec![
+            ethabi::Token::Tuple(vec![
+                ethabi::Token::Bool(true),
+                ethabi::Token::Bytes(vec![0x01]),
+            ]),
+            ethabi::Token::Tuple(vec![
+                ethabi::Token::Bool(false),
+                ethabi::Token::Bytes(error.clone()),
+            ]),
+        ])]);
+        assert_eq!(
+            decode_aggregate3(&output).unwrap(),
+            vec![
+                MulticallResult {
+                    success: true,
+                    output: "0x01".to_string(),
+                    error: None,
+                },
+                MulticallResult {
+                    success: false,
+                    output: format!("0x{}", hex::encode(&error)),
+                    error: Some("E1".to_string()),
+                },
+            ]
+        );
+        assert!(matches!(
+            decode_aggregate3(&[0x01]),
+            Err(BridgeError::InvalidOutput(_))
+        ));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            encode_call("approve(address,uint256)", &["1"]),
+            Err(BridgeError::ParameterCount {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert!(matches!(
+            decode_output("(uint256)", &[0x01]),
+            Err(BridgeError::InvalidOutput(_))
+        ));
+        assert!(parse_address("0x123").is_err());
+    }
+}
@@ -11,6 +11,8 @@ pub type RawAddress = [u8; 20];
 pub trait AuroraContract {
     #[result_serializer(borsh)]
     fn call(&self, #[serializer(borsh)] call_args: CallArgs);
+    #[result_serializer(borsh)]
+    fn view(&self, #[serializer(borsh)] view_args: ViewCallArgs);
 }
 
 // Aurora result
@@ -39,6 +41,18 @@ pub struct SubmitResult {
     pub logs: Vec<ResultLog>,
 }
 
+#[cfg(test)]
+impl SubmitResult {
+    pub fn new(status: TransactionStatus, gas_used: u64, logs: Vec<ResultLog>) -> Self {
+        Self {
+            version: 7,
+            status,
+            gas_used,
+            logs,
+        }
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Clone)]
 pub struct FunctionCallArgsV2 {
     pub contract: RawAddress,
@@ -46,6 +60,15 @@ pub struct FunctionCallArgsV2 {
     pub input: Vec<u8>,
 }
 
+/// Arguments of Aurora's `view`, which simulates a call from `sender` without changing state.
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct ViewCallArgs {
+    pub sender: RawAddress,
+    pub address: RawAddress,
+    pub amount: RawU256,
+    pub input: Vec<u8>,
+}
+
 // Enum from Aurora
 #[derive(BorshSerialize, BorshDeserialize, Clone)]
 pub enum CallArgs {
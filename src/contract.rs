@@ -0,0 +1,1604 @@
+// The contract methods take their optional arguments one by one, as named JSON arguments
+#![allow(clippy::too_many_arguments)]
+
+use crate::aurora::{
+    CallArgs, FunctionCallArgsV2, RawAddress, SubmitResult, TransactionStatus, ViewCallArgs,
+};
+use crate::error::UnwrapOrPanic;
+use crate::output::{CallOutput, CallReport, DeployOutput, MulticallOutput, ViewOutput};
+use crate::roles::Grant;
+use crate::utils::Selector;
+use crate::{aurora, output, utils};
+use aurora_engine_types::types::RawU256;
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    collections::UnorderedMap,
+    env, near_bindgen,
+    serde::{Deserialize, Serialize},
+    serde_json::Value,
+    AccountId, BorshStorageKey, Gas, PanicOnDefault, Promise, PromiseOrValue, PromiseResult,
+};
+use std::convert::TryFrom;
+
+/// Gas kept for the rest of the method dispatching the Aurora call.
+const GAS_FOR_DISPATCH: Gas = Gas(5 * Gas::ONE_TERA.0);
+
+/// Aurora network the bridge calls, which sets its engine account. Silos, and local or
+/// other private deployments, run their own engine account.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Silo(AccountId),
+}
+
+impl Network {
+    pub fn engine_account_id(&self) -> AccountId {
+        match self {
+            Network::Mainnet | Network::Testnet => "aurora"
+                .parse()
+                .expect("Internal error: Aurora address is not correct"),
+            Network::Silo(account_id) => account_id.clone(),
+        }
+    }
+}
+
+/// Gas of the Aurora calls and of their callbacks, when the caller doesn't give them.
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GasConfig {
+    pub gas: Gas,
+    pub callback_gas: Gas,
+}
+
+impl Default for GasConfig {
+    fn default() -> Self {
+        Self {
+            gas: Gas(100 * Gas::ONE_TERA.0),
+            callback_gas: Gas(10 * Gas::ONE_TERA.0),
+        }
+    }
+}
+
+impl GasConfig {
+    /// Gas of `calls` Aurora calls and of their callbacks, `None` if it overflows.
+    fn calls_gas(&self, calls: usize) -> Option<u64> {
+        let call_gas = self.gas.0.checked_add(self.callback_gas.0)?;
+        u64::try_from(calls).ok()?.checked_mul(call_gas)
+    }
+}
+
+/// A call of `batch_call`, with the same arguments as `function_call`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchItem {
+    pub aurora_address: String,
+    pub function: String,
+    pub parameters: Vec<String>,
+    pub value: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EngineView {
+    pub network: Network,
+    pub engine_account_id: AccountId,
+}
+
+#[derive(BorshStorageKey, BorshSerialize)]
+enum StorageKey {
+    Grants,
+    Errors,
+}
+
+#[near_bindgen]
+#[derive(PanicOnDefault, BorshDeserialize, BorshSerialize)]
+pub struct ContractBridge {
+    pub(crate) owner_id: AccountId,
+    pending_owner_id: Option<AccountId>,
+    pub(crate) grants: UnorderedMap<AccountId, Grant>,
+    /// Custom Solidity errors used to decode revert payloads, by selector.
+    errors: UnorderedMap<Selector, String>,
+    network: Network,
+    gas_config: GasConfig,
+    /// Multicall3 contract on Aurora used by `multicall`.
+    multicall: Option<RawAddress>,
+    /// CREATE2 factory on Aurora used by `deploy_create2`.
+    create2_factory: Option<RawAddress>,
+}
+
+#[near_bindgen]
+impl ContractBridge {
+    /// Initializes the contract. Only the contract account can initialize it, so nobody can
+    /// take ownership of a deployed but uninitialized bridge. The owner defaults to the contract
+    /// account and the network to `Mainnet`.
+    #[init]
+    #[private]
+    pub fn new(owner_id: Option<AccountId>, network: Option<Network>) -> Self {
+        assert!(!env::state_exists(), "Already initialized");
+        Self {
+            owner_id: owner_id.unwrap_or_else(env::current_account_id),
+            pending_owner_id: None,
+            grants: UnorderedMap::new(StorageKey::Grants),
+            errors: UnorderedMap::new(StorageKey::Errors),
+            network: network.unwrap_or(Network::Mainnet),
+            gas_config: GasConfig::default(),
+            multicall: None,
+            create2_factory: None,
+        }
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+
+    pub fn get_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner_id.clone()
+    }
+
+    /// First step of the ownership handover, the new owner must call `accept_ownership`.
+    pub fn transfer_ownership(&mut self, new_owner_id: AccountId) {
+        self.assert_owner();
+        self.pending_owner_id = Some(new_owner_id);
+    }
+
+    pub fn accept_ownership(&mut self) {
+        let predecessor = env::predecessor_account_id();
+        assert_eq!(
+            self.pending_owner_id.as_ref(),
+            Some(&predecessor),
+            "Only the pending owner can accept ownership"
+        );
+        self.owner_id = predecessor;
+        self.pending_owner_id = None;
+    }
+
+    /// Switches the Aurora network, and so the engine account, the bridge calls.
+    pub fn set_network(&mut self, network: Network) {
+        self.assert_owner();
+        self.network = network;
+    }
+
+    pub fn get_engine(&self) -> EngineView {
+        EngineView {
+            network: self.network.clone(),
+            engine_account_id: self.network.engine_account_id(),
+        }
+    }
+
+    /// Sets the gas of the Aurora calls and of their callbacks used when the caller doesn't
+    /// give them.
+    pub fn set_gas_config(&mut self, gas_config: GasConfig) {
+        self.assert_owner();
+        assert!(
+            gas_config.gas.0 > 0 && gas_config.callback_gas.0 > 0,
+            "The gas and callback_gas must not be zero"
+        );
+        self.gas_config = gas_config;
+    }
+
+    pub fn get_gas_config(&self) -> GasConfig {
+        self.gas_config
+    }
+
+    /// Sets the Multicall3 contract on Aurora used by `multicall`, or unsets it.
+    pub fn set_multicall(&mut self, aurora_address: Option<String>) {
+        self.assert_owner();
+        self.multicall = aurora_address
+            .map(|address| utils::from_string_to_address(&address).unwrap_or_panic().0);
+    }
+
+    pub fn get_multicall(&self) -> Option<String> {
+        self.multicall
+            .as_ref()
+            .map(|address| output::to_hex(address))
+    }
+
+    /// Sets the CREATE2 factory on Aurora used by `deploy_create2`, or unsets it. The factory
+    /// takes the salt followed by the init code as input and returns the new address, like the
+    /// deterministic deployment proxy at `0x4e59b44847b379578588920ca78fbf26c0b4956c`.
+    pub fn set_create2_factory(&mut self, aurora_address: Option<String>) {
+        self.assert_owner();
+        self.create2_factory = aurora_address
+            .map(|address| utils::from_string_to_address(&address).unwrap_or_panic().0);
+    }
+
+    pub fn get_create2_factory(&self) -> Option<String> {
+        self.create2_factory
+            .as_ref()
+            .map(|address| output::to_hex(address))
+    }
+
+    /// Address of a contract deployed with CREATE2 by `factory` with `salt`, where
+    /// `init_code_hash` is the keccak of the bytecode followed by the constructor arguments.
+    pub fn predict_create2_address(
+        &self,
+        factory: String,
+        salt: String,
+        init_code_hash: String,
+    ) -> String {
+        let factory = utils::from_string_to_address(&factory)
+            .map_err(|error| error.at("factory"))
+            .unwrap_or_panic();
+        let salt = utils::parse_bytes32(&salt)
+            .map_err(|error| error.at("salt"))
+            .unwrap_or_panic();
+        let init_code_hash = utils::parse_bytes32(&init_code_hash)
+            .map_err(|error| error.at("init_code_hash"))
+            .unwrap_or_panic();
+
+        output::to_hex(&utils::create2_address(&factory.0, &salt, &init_code_hash))
+    }
+
+    /// Registers a custom Solidity error (e.g. `"InsufficientBalance(uint256,uint256)"`)
+    /// so it can be named and decoded when an Aurora call reverts with it.
+    pub fn register_error(&mut self, signature: String) {
+        self.assert_owner();
+        let signature = utils::canonical_signature(&signature).unwrap_or_panic();
+        output::error_types(&signature).unwrap_or_panic();
+        self.errors.insert(
+            &utils::get_selector(&signature).unwrap_or_panic(),
+            &signature,
+        );
+    }
+
+    pub fn unregister_error(&mut self, signature: String) {
+        self.assert_owner();
+        self.errors
+            .remove(&utils::get_selector(&signature).unwrap_or_panic());
+    }
+
+    pub fn get_errors(&self) -> Vec<String> {
+        self.errors.values().collect()
+    }
+
+    /// Calls `function` on `aurora_address`, attaching `value` wei (e.g. `"1.5 ether"` or
+    /// `"0x3e8"`) from the bridge address if given. `gas` and `callback_gas` default to the
+    /// gas config of the contract.
+    pub fn function_call(
+        &mut self,
+        aurora_address: String,
+        function: String,
+        parameters: Vec<String>,
+        returns: Option<String>,
+        value: Option<String>,
+        gas: Option<Gas>,
+        callback_gas: Option<Gas>,
+    ) -> Promise {
+        let aurora_contract = utils::from_string_to_address(&aurora_address).unwrap_or_panic();
+        self.assert_can_call(&aurora_contract.0, &function);
+        let value = self.attached_value(value);
+
+        let input = utils::solidity_function(&function, &parameters).unwrap_or_panic();
+        let gas = self.gas_budget(gas, callback_gas);
+        self.call_aurora(aurora_contract.0, input, value, returns, gas)
+    }
+
+    /// Same as `function_call`, with JSON parameters instead of strings, e.g.
+    /// `[[1, 2], true, {"amount": "1.5 ether", "to": "0x..."}]`.
+    pub fn function_call_json(
+        &mut self,
+        aurora_address: String,
+        function: String,
+        parameters: Vec<Value>,
+        returns: Option<String>,
+        value: Option<String>,
+        gas: Option<Gas>,
+        callback_gas: Option<Gas>,
+    ) -> Promise {
+        let aurora_contract = utils::from_string_to_address(&aurora_address).unwrap_or_panic();
+        self.assert_can_call(&aurora_contract.0, &function);
+        let value = self.attached_value(value);
+
+        let input = utils::solidity_function_json(&function, &parameters).unwrap_or_panic();
+        let gas = self.gas_budget(gas, callback_gas);
+        self.call_aurora(aurora_contract.0, input, value, returns, gas)
+    }
+
+    /// Calls Aurora contracts one after the other, e.g. `approve` and then `deposit`, and
+    /// reports the outcome of each call. Unless `stop_on_revert` is false, the calls after the
+    /// first failed one are skipped. The calls are not atomic, earlier calls stay applied.
+    /// `gas` and `callback_gas` apply to each call and default to the gas config of the contract.
+    pub fn batch_call(
+        &mut self,
+        calls: Vec<BatchItem>,
+        stop_on_revert: Option<bool>,
+        gas: Option<Gas>,
+        callback_gas: Option<Gas>,
+    ) -> Promise {
+        assert!(!calls.is_empty(), "Empty batch");
+        let calls = calls
+            .into_iter()
+            .enumerate()
+            .map(|(i, call)| {
+                let path = format!("calls[{}]", i);
+                let contract = utils::from_string_to_address(&call.aurora_address)
+                    .map_err(|error| error.within(&path))
+                    .unwrap_or_panic()
+                    .0;
+                self.assert_can_call(&contract, &call.function);
+
+                FunctionCallArgsV2 {
+                    contract,
+                    value: self.attached_value(call.value),
+                    input: utils::solidity_function(&call.function, &call.parameters)
+                        .map_err(|error| error.within(&path))
+                        .unwrap_or_panic(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let gas_config = GasConfig {
+            gas: gas.unwrap_or(self.gas_config.gas),
+            callback_gas: callback_gas.unwrap_or(self.gas_config.callback_gas),
+        };
+        Self::assert_gas_left(gas_config.calls_gas(calls.len()));
+        self.dispatch_batch(calls, vec![], stop_on_revert.unwrap_or(true), gas_config)
+    }
+
+    /// Makes `calls` in a single Aurora call through the Multicall3 contract set with
+    /// `set_multicall`, so they all revert if one of them does, unless `allow_failure` is set.
+    /// The calls are made from the Multicall3 contract, not from the bridge address, and can't
+    /// attach value.
+    pub fn multicall(
+        &mut self,
+        calls: Vec<BatchItem>,
+        allow_failure: Option<bool>,
+        gas: Option<Gas>,
+        callback_gas: Option<Gas>,
+    ) -> Promise {
+        let multicall = self.multicall.expect("No multicall contract set");
+        assert!(!calls.is_empty(), "Empty batch");
+        let calls = calls
+            .into_iter()
+            .enumerate()
+            .map(|(i, call)| {
+                let path = format!("calls[{}]", i);
+                let contract = utils::from_string_to_address(&call.aurora_address)
+                    .map_err(|error| error.within(&path))
+                    .unwrap_or_panic()
+                    .0;
+                self.assert_can_call(&contract, &call.function);
+                assert!(
+                    call.value.is_none(),
+                    "{}: multicall calls can't attach value",
+                    path
+                );
+
+                let input = utils::solidity_function(&call.function, &call.parameters)
+                    .map_err(|error| error.within(&path))
+                    .unwrap_or_panic();
+                (contract, input)
+            })
+            .collect::<Vec<_>>();
+
+        let input = utils::aggregate3(&calls, allow_failure.unwrap_or(false)).unwrap_or_panic();
+        let (gas, callback_gas) = self.gas_budget(gas, callback_gas);
+
+        aurora::ext_aurora::ext(self.network.engine_account_id())
+            .with_static_gas(gas)
+            .call(CallArgs::V2(FunctionCallArgsV2 {
+                contract: multicall,
+                value: RawU256::default(),
+                input,
+            }))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(callback_gas)
+                    .on_multicall_result(),
+            )
+    }
+
+    /// Deploys the hex `bytecode` on Aurora from the bridge address, followed by the arguments
+    /// of `constructor` (e.g. `"constructor(string name, uint8 decimals)"`) if given. Only the
+    /// owner and admins can deploy.
+    pub fn deploy(
+        &mut self,
+        bytecode: String,
+        constructor: Option<String>,
+        parameters: Option<Vec<String>>,
+        gas: Option<Gas>,
+        callback_gas: Option<Gas>,
+    ) -> Promise {
+        self.assert_can_deploy();
+        let input = utils::deploy_input(
+            &bytecode,
+            constructor.as_deref(),
+            &parameters.unwrap_or_default(),
+        )
+        .unwrap_or_panic();
+        let (gas, callback_gas) = self.gas_budget(gas, callback_gas);
+
+        // `deploy_code` reads the init code as is, not as borsh arguments
+        Promise::new(self.network.engine_account_id())
+            .function_call("deploy_code".to_string(), input, 0, gas)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(callback_gas)
+                    .on_deploy_result(),
+            )
+    }
+
+    /// Same as `deploy`, through the CREATE2 factory set with `set_create2_factory`, so the
+    /// address of the contract only depends on the factory, `salt` (32 bytes hex) and the init
+    /// code, see `predict_create2_address`.
+    pub fn deploy_create2(
+        &mut self,
+        bytecode: String,
+        constructor: Option<String>,
+        parameters: Option<Vec<String>>,
+        salt: String,
+        gas: Option<Gas>,
+        callback_gas: Option<Gas>,
+    ) -> Promise {
+        self.assert_can_deploy();
+        let factory = self.create2_factory.expect("No CREATE2 factory set");
+        let salt = utils::parse_bytes32(&salt)
+            .map_err(|error| error.at("salt"))
+            .unwrap_or_panic();
+        let init_code = utils::deploy_input(
+            &bytecode,
+            constructor.as_deref(),
+            &parameters.unwrap_or_default(),
+        )
+        .unwrap_or_panic();
+        let (gas, callback_gas) = self.gas_budget(gas, callback_gas);
+
+        aurora::ext_aurora::ext(self.network.engine_account_id())
+            .with_static_gas(gas)
+            .call(CallArgs::V2(FunctionCallArgsV2 {
+                contract: factory,
+                value: RawU256::default(),
+                input: [&salt[..], &init_code].concat(),
+            }))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(callback_gas)
+                    .on_deploy_result(),
+            )
+    }
+
+    /// Simulates a call to `function` on `aurora_address` through Aurora's `view`, e.g.
+    /// `balanceOf(address)`, without changing any state on Aurora. The call is made from
+    /// `sender`, or from the address of the bridge if not given. Only the owner and the accounts
+    /// with a role, `Viewer` included, can make view calls.
+    pub fn view_call(
+        &self,
+        aurora_address: String,
+        function: String,
+        parameters: Vec<String>,
+        sender: Option<String>,
+        returns: Option<String>,
+        gas: Option<Gas>,
+        callback_gas: Option<Gas>,
+    ) -> Promise {
+        self.assert_can_view();
+        let aurora_contract = utils::from_string_to_address(&aurora_address).unwrap_or_panic();
+        let sender = match sender {
+            Some(sender) => {
+                utils::from_string_to_address(&sender)
+                    .map_err(|error| error.at("sender"))
+                    .unwrap_or_panic()
+                    .0
+            }
+            None => utils::near_account_to_address(env::current_account_id().as_str()),
+        };
+        let input = utils::solidity_function(&function, &parameters).unwrap_or_panic();
+        Self::check_returns(&returns);
+        let (gas, callback_gas) = self.gas_budget(gas, callback_gas);
+
+        aurora::ext_aurora::ext(self.network.engine_account_id())
+            .with_static_gas(gas)
+            .view(ViewCallArgs {
+                sender,
+                address: aurora_contract.0,
+                amount: RawU256::default(),
+                input,
+            })
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(callback_gas)
+                    .on_view_result(returns),
+            )
+    }
+
+    /// Decodes the `SubmitResult` returned by Aurora, failing the receipt with the decoded
+    /// revert reason if the EVM call failed. The EVM output is ABI decoded when a `returns`
+    /// signature (e.g. `"(uint256)"`) is given.
+    #[private]
+    pub fn on_call_result(&self, returns: Option<String>) -> CallOutput {
+        let result = Self::submit_result("call");
+
+        output::call_output(result, returns.as_deref(), |selector| {
+            self.errors.get(selector)
+        })
+    }
+
+    /// Reports the result of a call of `batch_call` and dispatches the next one, if any.
+    #[private]
+    pub fn on_batch_result(
+        &self,
+        #[serializer(borsh)] calls: Vec<FunctionCallArgsV2>,
+        #[serializer(borsh)] reports: Vec<CallReport>,
+        #[serializer(borsh)] stop_on_revert: bool,
+        #[serializer(borsh)] gas_config: GasConfig,
+    ) -> PromiseOrValue<Vec<CallReport>> {
+        let report = match env::promise_result(0) {
+            PromiseResult::Successful(result) => match SubmitResult::try_from_slice(&result) {
+                Ok(result) => output::call_report(result, |selector| self.errors.get(selector)),
+                Err(_) => CallReport::failed("Aurora result is not a SubmitResult"),
+            },
+            _ => CallReport::failed("Aurora call failed"),
+        };
+        let stop = stop_on_revert && !report.succeeded();
+        let reports = [reports, vec![report]].concat();
+
+        if stop {
+            let skipped = calls.iter().map(|_| CallReport::skipped());
+            PromiseOrValue::Value(reports.into_iter().chain(skipped).collect())
+        } else if calls.is_empty() {
+            PromiseOrValue::Value(reports)
+        } else {
+            PromiseOrValue::Promise(self.dispatch_batch(calls, reports, stop_on_revert, gas_config))
+        }
+    }
+
+    /// Same as `on_call_result` for `multicall`, with the result of each call.
+    #[private]
+    pub fn on_multicall_result(&self) -> MulticallOutput {
+        let result = Self::submit_result("call");
+
+        output::multicall_output(result, |selector| self.errors.get(selector))
+    }
+
+    /// Same as `on_call_result` for `deploy`, with the address of the new contract.
+    #[private]
+    pub fn on_deploy_result(&self) -> DeployOutput {
+        let result = Self::submit_result("deploy");
+
+        output::deploy_output(result, |selector| self.errors.get(selector))
+    }
+
+    /// Same as `on_call_result` for the `TransactionStatus` returned by Aurora's `view`.
+    #[private]
+    pub fn on_view_result(&self, returns: Option<String>) -> ViewOutput {
+        let status = match env::promise_result(0) {
+            PromiseResult::Successful(status) => status,
+            _ => panic!("Aurora view failed"),
+        };
+        let status = TransactionStatus::try_from_slice(&status)
+            .expect("Aurora result is not a TransactionStatus");
+
+        output::view_output(status, returns.as_deref(), |selector| {
+            self.errors.get(selector)
+        })
+    }
+}
+
+impl ContractBridge {
+    fn call_aurora(
+        &self,
+        aurora_contract: RawAddress,
+        input: Vec<u8>,
+        value: RawU256,
+        returns: Option<String>,
+        (gas, callback_gas): (Gas, Gas),
+    ) -> Promise {
+        Self::check_returns(&returns);
+
+        aurora::ext_aurora::ext(self.network.engine_account_id())
+            .with_static_gas(gas)
+            .call(CallArgs::V2(FunctionCallArgsV2 {
+                contract: aurora_contract,
+                value,
+                input,
+            }))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(callback_gas)
+                    .on_call_result(returns),
+            )
+    }
+
+    /// Calls the first of `calls`, its callback reports it and dispatches the rest, each call
+    /// with the gas of `gas_config`.
+    fn dispatch_batch(
+        &self,
+        mut calls: Vec<FunctionCallArgsV2>,
+        reports: Vec<CallReport>,
+        stop_on_revert: bool,
+        gas_config: GasConfig,
+    ) -> Promise {
+        let GasConfig { gas, callback_gas } = gas_config;
+        let call = calls.remove(0);
+        // The callback also dispatches the calls left and their callbacks
+        let batch_gas = gas_config
+            .calls_gas(calls.len())
+            .and_then(|calls_gas| calls_gas.checked_add(callback_gas.0))
+            // `batch_call` checked the whole batch fits in the attached gas
+            .map(Gas)
+            .expect("Internal error: batch gas overflow");
+
+        aurora::ext_aurora::ext(self.network.engine_account_id())
+            .with_static_gas(gas)
+            .call(CallArgs::V2(call))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(batch_gas)
+                    .on_batch_result(calls, reports, stop_on_revert, gas_config),
+            )
+    }
+
+    /// Gas of an Aurora call and of its callback, checking the gas left covers both before
+    /// anything is dispatched.
+    fn gas_budget(&self, gas: Option<Gas>, callback_gas: Option<Gas>) -> (Gas, Gas) {
+        let gas = gas.unwrap_or(self.gas_config.gas);
+        let callback_gas = callback_gas.unwrap_or(self.gas_config.callback_gas);
+        Self::assert_gas_left(gas.0.checked_add(callback_gas.0));
+        (gas, callback_gas)
+    }
+
+    /// `gas` is `None` if summing it overflowed, which no attached gas can cover.
+    fn assert_gas_left(gas: Option<u64>) {
+        let needed = gas
+            .and_then(|gas| gas.checked_add(GAS_FOR_DISPATCH.0))
+            .unwrap_or(u64::MAX);
+        let left = env::prepaid_gas().0 - env::used_gas().0;
+        assert!(
+            left >= needed,
+            "Not enough gas attached, the Aurora call and its callback need {} TGas but only {} TGas are left",
+            needed / Gas::ONE_TERA.0,
+            left / Gas::ONE_TERA.0
+        );
+    }
+
+    /// `SubmitResult` of the Aurora `what` (e.g. `"call"`) a callback is attached to, failing
+    /// the receipt if the promise failed.
+    fn submit_result(what: &str) -> SubmitResult {
+        match env::promise_result(0) {
+            PromiseResult::Successful(result) => {
+                SubmitResult::try_from_slice(&result).expect("Aurora result is not a SubmitResult")
+            }
+            _ => panic!("Aurora {} failed", what),
+        }
+    }
+
+    /// Fails before calling Aurora if the return signature can't be decoded.
+    fn check_returns(returns: &Option<String>) {
+        if let Some(returns) = returns {
+            output::return_types(returns).unwrap_or_panic();
+        }
+    }
+
+    /// Parses the wei attached to a call, only the owner and admins can attach a non-zero value.
+    fn attached_value(&self, value: Option<String>) -> RawU256 {
+        let value = value
+            .map(|value| {
+                utils::parse_wei(&value)
+                    .map_err(|error| error.at("value"))
+                    .unwrap_or_panic()
+            })
+            .unwrap_or_default();
+        if value != RawU256::default() {
+            self.assert_can_send_value();
+        }
+        value
+    }
+
+    fn assert_owner(&self) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.owner_id,
+            "Only the owner can call this method"
+        );
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aurora::{ResultLog, TransactionStatus};
+    use crate::output::LogOutput;
+    use crate::roles::Role;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, RuntimeFeesConfig, VMConfig};
+
+    // @TODO: Tests in progress
+
+    fn get_context(predecessor_account_id: AccountId) -> VMContextBuilder {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .current_account_id(accounts(0))
+            .signer_account_id(predecessor_account_id.clone())
+            .predecessor_account_id(predecessor_account_id);
+        builder
+    }
+
+    #[test]
+    #[should_panic(expected = "The contract is not initialized")]
+    fn test_default() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+    }
+
+    #[test]
+    fn test_new() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+        assert_eq!(contract.get_owner(), accounts(0));
+
+        let contract = ContractBridge::new(Some(accounts(2)), None);
+        assert_eq!(contract.get_owner(), accounts(2));
+    }
+
+    #[test]
+    fn test_function_call_gas() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        contract.function_call(
+            "A5C7FDbe2a8B814369e89EAed7EE52630fcb4C59".to_string(),
+            "deposit()".to_string(),
+            vec![],
+            None,
+            None,
+            Some(Gas(200 * Gas::ONE_TERA.0)),
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Not enough gas attached, the Aurora call and its callback need 315 TGas"
+    )]
+    fn test_function_call_not_enough_gas() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        contract.function_call(
+            "A5C7FDbe2a8B814369e89EAed7EE52630fcb4C59".to_string(),
+            "deposit()".to_string(),
+            vec![],
+            None,
+            None,
+            Some(Gas(300 * Gas::ONE_TERA.0)),
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Not enough gas attached, the Aurora call and its callback need 18446744 TGas"
+    )]
+    fn test_function_call_gas_overflow() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        contract.function_call(
+            "A5C7FDbe2a8B814369e89EAed7EE52630fcb4C59".to_string(),
+            "deposit()".to_string(),
+            vec![],
+            None,
+            None,
+            Some(Gas(u64::MAX)),
+            Some(Gas(u64::MAX)),
+        );
+    }
+
+    #[test]
+    fn test_gas_config() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+        assert_eq!(contract.get_gas_config(), GasConfig::default());
+
+        let gas_config = GasConfig {
+            gas: Gas(50 * Gas::ONE_TERA.0),
+            callback_gas: Gas(5 * Gas::ONE_TERA.0),
+        };
+        contract.set_gas_config(gas_config);
+        assert_eq!(contract.get_gas_config(), gas_config);
+    }
+
+    #[test]
+    #[should_panic(expected = "The gas and callback_gas must not be zero")]
+    fn test_set_gas_config_zero() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        contract.set_gas_config(GasConfig {
+            gas: Gas(50 * Gas::ONE_TERA.0),
+            callback_gas: Gas(0),
+        });
+    }
+
+    #[test]
+    fn test_network() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, Some(Network::Testnet));
+        assert_eq!(
+            contract.get_engine(),
+            EngineView {
+                network: Network::Testnet,
+                engine_account_id: "aurora".parse().unwrap(),
+            }
+        );
+
+        let silo: AccountId = "engine.silo.near".parse().unwrap();
+        contract.set_network(Network::Silo(silo.clone()));
+        assert_eq!(contract.get_engine().engine_account_id, silo);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_set_network_not_owner() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.set_network(Network::Testnet);
+    }
+
+    #[test]
+    fn test_transfer_ownership() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        contract.transfer_ownership(accounts(2));
+        assert_eq!(contract.get_owner(), accounts(0));
+        assert_eq!(contract.get_pending_owner(), Some(accounts(2)));
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.accept_ownership();
+        assert_eq!(contract.get_owner(), accounts(2));
+        assert_eq!(contract.get_pending_owner(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_transfer_ownership_not_owner() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.transfer_ownership(accounts(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the pending owner can accept ownership")]
+    fn test_accept_ownership_not_pending_owner() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+        contract.transfer_ownership(accounts(2));
+
+        testing_env!(context.predecessor_account_id(accounts(3)).build());
+        contract.accept_ownership();
+    }
+
+    #[test]
+    #[should_panic(expected = "is not allowed to call approve(address,uint256)")]
+    fn test_function_call_not_owner() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.function_call(
+            "A5C7FDbe2a8B814369e89EAed7EE52630fcb4C59".to_string(),
+            "approve(address,uint256)".to_string(),
+            vec![
+                "6eba6ef721bd532ca8e5d5ec8faf7f0fa0dac474".to_string(),
+                "1".to_string(),
+            ],
+            None,
+            None,
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "is not allowed to attach value to Aurora calls")]
+    fn test_function_call_value_not_allowed() {
+        let mut context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+        contract.grant_role(accounts(2), Role::Operator);
+        contract.allow_call(
+            accounts(2),
+            "A5C7FDbe2a8B814369e89EAed7EE52630fcb4C59".to_string(),
+            vec!["deposit()".to_string()],
+        );
+
+        testing_env!(context.predecessor_account_id(accounts(2)).build());
+        contract.function_call(
+            "A5C7FDbe2a8B814369e89EAed7EE52630fcb4C59".to_string(),
+            "deposit()".to_string(),
+            vec![],
+            None,
+            Some("1 ether".to_string()),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "value: Invalid input parameter: InvalidCharacter")]
+    fn test_function_call_invalid_value() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        contract.function_call(
+            "A5C7FDbe2a8B814369e89EAed7EE52630fcb4C59".to_string(),
+            "deposit()".to_string(),
+            vec![],
+            None,
+            Some("one ether".to_string()),
+            None,
+            None,
+        );
+    }
+
+    fn set_promise_result(context: &VMContextBuilder, result: impl BorshSerialize) {
+        testing_env!(
+            context.build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(result.try_to_vec().unwrap())],
+        );
+    }
+
+    fn batch_item(function: &str, parameters: &[&str]) -> BatchItem {
+        BatchItem {
+            aurora_address: "A5C7FDbe2a8B814369e89EAed7EE52630fcb4C59".to_string(),
+            function: function.to_string(),
+            parameters: parameters.iter().map(|p| p.to_string()).collect(),
+            value: None,
+        }
+    }
+
+    #[test]
+    fn test_batch_call() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        contract.batch_call(
+            vec![
+                batch_item(
+                    "approve(address,uint256)",
+                    &["6eba6ef721bd532ca8e5d5ec8faf7f0fa0dac474", "1"],
+                ),
+                batch_item("deposit(uint256)", &["1"]),
+            ],
+            None,
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    fn test_batch_call_gas() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        // Three calls don't fit in 300 TGas with the default gas config
+        drop(contract.batch_call(
+            vec![batch_item("deposit(uint256)", &["1"]); 3],
+            None,
+            Some(Gas(50 * Gas::ONE_TERA.0)),
+            Some(Gas(10 * Gas::ONE_TERA.0)),
+        ));
+
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        let gas = |receipt: &near_sdk::mock::Receipt| match &receipt.actions[0] {
+            near_sdk::mock::VmAction::FunctionCall { gas, .. } => *gas,
+            action => panic!("Unexpected action {:?}", action),
+        };
+        assert_eq!(gas(&receipts[0]), Gas(50 * Gas::ONE_TERA.0));
+        // The callback dispatches the two calls left
+        assert_eq!(gas(&receipts[1]), Gas(130 * Gas::ONE_TERA.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "need 335 TGas")]
+    fn test_batch_call_not_enough_gas() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        contract.batch_call(
+            vec![batch_item("deposit(uint256)", &["1"]); 3],
+            None,
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "need 18446744 TGas")]
+    fn test_batch_call_gas_overflow() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        contract.batch_call(
+            vec![batch_item("deposit(uint256)", &["1"]); 3],
+            None,
+            Some(Gas(u64::MAX / 2)),
+            Some(Gas(1)),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "calls[2].parameters[0]: Invalid input parameter")]
+    fn test_batch_call_invalid_item() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        contract.batch_call(
+            vec![
+                batch_item("deposit(uint256)", &["1"]),
+                batch_item("deposit(int8)", &["-1"]),
+                batch_item("deposit(uint256)", &["-1"]),
+            ],
+            None,
+            None,
+            None,
+        );
+    }
+
+    fn pending_call() -> FunctionCallArgsV2 {
+        FunctionCallArgsV2 {
+            contract: [0x11; 20],
+            value: RawU256::default(),
+            input: vec![],
+        }
+    }
+
+    #[test]
+    fn test_on_batch_result_stop_on_revert() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        let error = [
+            vec![0x08, 0xc3, 0x79, 0xa0],
+            ethabi::encode(&[ethabi::Token::String("E1".to_string())]),
+        ]
+        .concat();
+        set_promise_result(
+            &context,
+            SubmitResult::new(TransactionStatus::Revert(error), 21000, vec![]),
+        );
+        let succeeded = CallReport {
+            status: "Succeed".to_string(),
+            gas_used: 21000,
+            output: Some("0x".to_string()),
+            error: None,
+        };
+
+        match contract.on_batch_result(
+            vec![pending_call()],
+            vec![succeeded.clone()],
+            true,
+            GasConfig::default(),
+        ) {
+            PromiseOrValue::Value(reports) => assert_eq!(
+                reports,
+                vec![
+                    succeeded,
+                    CallReport {
+                        status: "Revert".to_string(),
+                        gas_used: 21000,
+                        output: None,
+                        error: Some("E1".to_string()),
+                    },
+                    CallReport::skipped(),
+                ]
+            ),
+            PromiseOrValue::Promise(_) => panic!("The batch should stop"),
+        }
+    }
+
+    #[test]
+    fn test_on_batch_result_continue() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        set_promise_result(
+            &context,
+            SubmitResult::new(TransactionStatus::Revert(vec![]), 21000, vec![]),
+        );
+        assert!(matches!(
+            contract.on_batch_result(vec![pending_call()], vec![], false, GasConfig::default()),
+            PromiseOrValue::Promise(_)
+        ));
+
+        set_promise_result(
+            &context,
+            SubmitResult::new(TransactionStatus::Succeed(vec![0x01]), 21000, vec![]),
+        );
+        match contract.on_batch_result(vec![], vec![], true, GasConfig::default()) {
+            PromiseOrValue::Value(reports) => {
+                assert_eq!(reports.len(), 1);
+                assert_eq!(reports[0].output, Some("0x01".to_string()));
+            }
+            PromiseOrValue::Promise(_) => panic!("The batch should be over"),
+        }
+    }
+
+    #[test]
+    fn test_multicall() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+        assert_eq!(contract.get_multicall(), None);
+
+        contract.set_multicall(Some("cA11bde05977b3631167028862bE2a173976CA11".to_string()));
+        assert_eq!(
+            contract.get_multicall(),
+            Some("0xca11bde05977b3631167028862be2a173976ca11".to_string())
+        );
+        contract.multicall(
+            vec![
+                batch_item("deposit(uint256)", &["1"]),
+                batch_item("withdraw(uint256)", &["1"]),
+            ],
+            None,
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "No multicall contract set")]
+    fn test_multicall_not_set() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        contract.multicall(
+            vec![batch_item("deposit(uint256)", &["1"])],
+            None,
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "calls[0]: multicall calls can't attach value")]
+    fn test_multicall_value() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+        contract.set_multicall(Some("ca11bde05977b3631167028862be2a173976ca11".to_string()));
+
+        let mut call = batch_item("deposit(uint256)", &["1"]);
+        call.value = Some("1 ether".to_string());
+        contract.multicall(vec![call], None, None, None);
+    }
+
+    #[test]
+    fn test_on_multicall_result() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        let output = ethabi::encode(&[ethabi::Token::Array(vec![
+            ethabi::Token::Tuple(vec![
+                ethabi::Token::Bool(true),
+                ethabi::Token::Bytes(vec![]),
+            ]),
+            ethabi::Token::Tuple(vec![
+                ethabi::Token::Bool(true),
+                ethabi::Token::Bytes(vec![0x01]),
+            ]),
+        ])]);
+        set_promise_result(
+            &context,
+            SubmitResult::new(TransactionStatus::Succeed(output), 50000, vec![]),
+        );
+
+        let output = contract.on_multicall_result();
+        assert_eq!(output.gas_used, 50000);
+        assert_eq!(
+            output
+                .results
+                .iter()
+                .map(|result| (result.success, result.output.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(true, "0x"), (true, "0x01")]
+        );
+    }
+
+    #[test]
+    fn test_on_multicall_result_invalid_output() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        set_promise_result(
+            &context,
+            SubmitResult::new(TransactionStatus::Succeed(vec![0x01]), 50000, vec![]),
+        );
+        let output = contract.on_multicall_result();
+        assert!(output.results.is_empty());
+        assert!(output.decode_error.is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "Aurora call reverted: Multicall3: call failed")]
+    fn test_on_multicall_result_revert() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        let error = [
+            vec![0x08, 0xc3, 0x79, 0xa0],
+            ethabi::encode(&[ethabi::Token::String("Multicall3: call failed".to_string())]),
+        ]
+        .concat();
+        set_promise_result(
+            &context,
+            SubmitResult::new(TransactionStatus::Revert(error), 50000, vec![]),
+        );
+        contract.on_multicall_result();
+    }
+
+    #[test]
+    fn test_deploy() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        drop(contract.deploy(
+            "0x6080".to_string(),
+            Some("constructor(string name)".to_string()),
+            Some(vec!["Token".to_string()]),
+            None,
+            None,
+        ));
+
+        // The init code is passed as is, not as a borsh `Vec<u8>`
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        assert_eq!(
+            receipts[0].actions[0],
+            near_sdk::mock::VmAction::FunctionCall {
+                function_name: "deploy_code".to_string(),
+                args: [
+                    vec![0x60, 0x80],
+                    ethabi::encode(&[ethabi::Token::String("Token".to_string())])
+                ]
+                .concat(),
+                gas: GasConfig::default().gas,
+                deposit: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_deploy_create2() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+        contract.set_create2_factory(Some("4e59b44847b379578588920ca78fbf26c0b4956c".to_string()));
+        assert_eq!(
+            contract.get_create2_factory(),
+            Some("0x4e59b44847b379578588920ca78fbf26c0b4956c".to_string())
+        );
+
+        drop(contract.deploy_create2(
+            "0x6080".to_string(),
+            None,
+            None,
+            format!("0x{}", "01".repeat(32)),
+            None,
+            None,
+        ));
+
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        let call_args = CallArgs::V2(FunctionCallArgsV2 {
+            contract: utils::from_string_to_address("4e59b44847b379578588920ca78fbf26c0b4956c")
+                .unwrap()
+                .0,
+            value: RawU256::default(),
+            input: [vec![0x01; 32], vec![0x60, 0x80]].concat(),
+        });
+        assert_eq!(
+            receipts[0].actions[0],
+            near_sdk::mock::VmAction::FunctionCall {
+                function_name: "call".to_string(),
+                args: call_args.try_to_vec().unwrap(),
+                gas: GasConfig::default().gas,
+                deposit: 0,
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "No CREATE2 factory set")]
+    fn test_deploy_create2_not_set() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+
+        contract.deploy_create2(
+            "0x6080".to_string(),
+            None,
+            None,
+            format!("0x{}", "01".repeat(32)),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    fn test_predict_create2_address() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        assert_eq!(
+            contract.predict_create2_address(
+                "deadbeef00000000000000000000000000000000".to_string(),
+                format!("0x{}", "00".repeat(32)),
+                hex::encode(utils::keccak(&[0x00])),
+            ),
+            "0xb928f69bb1d91cd65274e3c79d8986362984fda3"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "salt: The value 0x01 has 1 bytes, expected exactly 32 for bytes32")]
+    fn test_predict_create2_address_invalid_salt() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        contract.predict_create2_address(
+            "deadbeef00000000000000000000000000000000".to_string(),
+            "0x01".to_string(),
+            hex::encode(utils::keccak(&[0x00])),
+        );
+    }
+
+    #[test]
+    fn test_on_deploy_result() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        set_promise_result(
+            &context,
+            SubmitResult::new(TransactionStatus::Succeed(vec![0x11; 20]), 500000, vec![]),
+        );
+        assert_eq!(
+            contract.on_deploy_result(),
+            DeployOutput {
+                status: "Succeed".to_string(),
+                gas_used: 500000,
+                address: "0x1111111111111111111111111111111111111111".to_string(),
+                logs: vec![],
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Aurora deploy didn't return an address")]
+    fn test_on_deploy_result_no_address() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        set_promise_result(
+            &context,
+            SubmitResult::new(TransactionStatus::Succeed(vec![]), 500000, vec![]),
+        );
+        contract.on_deploy_result();
+    }
+
+    #[test]
+    fn test_on_view_result() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        set_promise_result(
+            &context,
+            TransactionStatus::Succeed(ethabi::encode(&[ethabi::Token::Uint(1500.into())])),
+        );
+        assert_eq!(
+            contract.on_view_result(Some("(uint256 balance)".to_string())),
+            ViewOutput {
+                status: "Succeed".to_string(),
+                output: format!("0x{:064x}", 1500),
+                decoded: Some(vec![Value::String("1500".to_string())]),
+                decode_error: None,
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Aurora call reverted")]
+    fn test_on_view_result_revert() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        set_promise_result(&context, TransactionStatus::Revert(vec![]));
+        contract.on_view_result(None);
+    }
+
+    #[test]
+    fn test_on_call_result() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        set_promise_result(
+            &context,
+            SubmitResult::new(
+                TransactionStatus::Succeed(ethabi::encode(&[ethabi::Token::Uint(258.into())])),
+                21000,
+                vec![ResultLog {
+                    address: [0x11; 20],
+                    topics: vec![[0x22; 32]],
+                    data: vec![0x33],
+                }],
+            ),
+        );
+        assert_eq!(
+            contract.on_call_result(Some("(uint16)".to_string())),
+            CallOutput {
+                status: "Succeed".to_string(),
+                gas_used: 21000,
+                output: format!("0x{:064x}", 258),
+                logs: vec![LogOutput {
+                    address: format!("0x{}", "11".repeat(20)),
+                    topics: vec![format!("0x{}", "22".repeat(32))],
+                    data: "0x33".to_string(),
+                }],
+                decoded: Some(vec![near_sdk::serde_json::json!("258")]),
+                decode_error: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_on_call_result_output_mismatch() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        set_promise_result(
+            &context,
+            SubmitResult::new(TransactionStatus::Succeed(vec![0x01]), 21000, vec![]),
+        );
+        // The Aurora call succeeded, the receipt must not fail on the decoding
+        let output = contract.on_call_result(Some("(uint256)".to_string()));
+        assert_eq!(output.output, "0x01");
+        assert_eq!(output.decoded, None);
+        assert!(output
+            .decode_error
+            .unwrap()
+            .starts_with("Output doesn't match the return signature"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Aurora call reverted")]
+    fn test_on_call_result_revert() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        set_promise_result(
+            &context,
+            SubmitResult::new(TransactionStatus::Revert(vec![]), 21000, vec![]),
+        );
+        contract.on_call_result(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Aurora call ran out of gas")]
+    fn test_on_call_result_out_of_gas() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        set_promise_result(
+            &context,
+            SubmitResult::new(TransactionStatus::OutOfGas, 21000, vec![]),
+        );
+        contract.on_call_result(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Aurora call reverted: E1")]
+    fn test_on_call_result_revert_reason() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let contract = ContractBridge::new(None, None);
+
+        // Revert with `Error("E1")`
+        let result = hex::decode(
+            "07016400000008c379a00000000000000000000000000000000000000000000000000000000000000020\
+             000000000000000000000000000000000000000000000000000000000000000245310000000000000000\
+             00000000000000000000000000000000000000000000839200000000000000000000",
+        )
+        .unwrap();
+        testing_env!(
+            context.build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(result)],
+        );
+        contract.on_call_result(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Aurora call reverted: InsufficientBalance(100, 200)")]
+    fn test_on_call_result_custom_error() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = ContractBridge::new(None, None);
+        let signature = "InsufficientBalance(uint256,uint256)";
+        contract.register_error(signature.to_string());
+        assert_eq!(contract.get_errors(), vec![signature.to_string()]);
+
+        let data = [
+            utils::get_selector(signature).unwrap().to_vec(),
+            ethabi::encode(&[
+                ethabi::Token::Uint(100.into()),
+                ethabi::Token::Uint(200.into()),
+            ]),
+        ]
+        .concat();
+        set_promise_result(
+            &context,
+            SubmitResult::new(TransactionStatus::Revert(data), 21000, vec![]),
+        );
+        contract.on_call_result(None);
+    }
+
+    #[test]
+    fn test_output() {
+        let result = [
+            0x07, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x60, 0x56, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, // 0x07, 0x01, 0x64, 0x00, 0x00, 0x00, 0x08, 0xc3, 0x79, 0xa0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                  //     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                  //     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                  //     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                  //     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x45, 0x31, 0x00, 0x00, 0x00, 0x00,
+                  //     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                  //     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x83, 0x92, 0x00, 0x00, 0x00, 0x00,
+                  //     0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        ];
+
+        let result_decoded = SubmitResult::deserialize(&mut result.as_slice());
+        println!("{:?}", result_decoded);
+    }
+}
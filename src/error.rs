@@ -0,0 +1,192 @@
+use std::fmt;
+
+lazy_static! {
+    static ref ERROR_PARSING: &'static str = "Invalid input parameter";
+}
+
+pub type Result<T> = std::result::Result<T, BridgeError>;
+
+/// Errors of the encoding of Aurora calls: signatures, types and the values given for them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BridgeError {
+    /// A function signature that can't be parsed, e.g. missing its parentheses
+    InvalidSignature(String),
+    /// The number of values doesn't match the number of parameters of the signature
+    ParameterCount { expected: usize, actual: usize },
+    /// A type that isn't a Solidity type, e.g. `addrress`
+    UnsupportedType(String),
+    /// A type whose size is out of bounds, e.g. `uint257` or `bytes33`
+    InvalidTypeSize { kind: String, reason: String },
+    /// A value that can't be parsed as its type
+    InvalidValue {
+        kind: String,
+        value: String,
+        reason: String,
+    },
+    /// A JSON value of the wrong kind for its type, e.g. a boolean for a `uint256`
+    TypeMismatch { kind: String, value: String },
+    /// A malformed array or tuple value
+    InvalidList {
+        list: String,
+        position: usize,
+        reason: String,
+    },
+    /// A number that doesn't fit in its type
+    OutOfRange { kind: String, value: String },
+    /// A number with a fractional part, e.g. `1.5 wei`
+    NotAnInteger(String),
+    /// A `bytesN` value that isn't exactly N bytes long
+    BytesLength {
+        kind: String,
+        value: String,
+        expected: usize,
+        actual: usize,
+    },
+    /// A fixed array or tuple value with the wrong number of elements
+    LengthMismatch {
+        kind: String,
+        expected: usize,
+        actual: usize,
+    },
+    /// A JSON object missing a tuple component
+    MissingComponent { name: String, value: String },
+    /// A JSON object given for a tuple without component names
+    UnnamedComponents(String),
+    /// A mixed-case address that doesn't match its EIP-55 checksum
+    InvalidChecksum { address: String, expected: String },
+    /// An EVM output that doesn't match its return signature
+    InvalidOutput(String),
+    /// An error in the value at `path`, e.g. `parameters[1][0].2`
+    AtParameter {
+        path: String,
+        error: Box<BridgeError>,
+    },
+}
+
+impl BridgeError {
+    pub(crate) fn invalid_value(kind: &str, value: &str, reason: impl fmt::Debug) -> Self {
+        Self::InvalidValue {
+            kind: kind.to_string(),
+            value: value.to_string(),
+            reason: format!("{:?}", reason),
+        }
+    }
+
+    /// Locates the error at `path`, unless it was already located deeper in the value.
+    pub(crate) fn at(self, path: &str) -> Self {
+        match self {
+            error @ Self::AtParameter { .. } => error,
+            error => Self::AtParameter {
+                path: path.to_string(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Locates the error inside `path`, e.g. `calls[2]` for a call of a batch.
+    #[cfg(feature = "contract")]
+    pub(crate) fn within(self, path: &str) -> Self {
+        match self {
+            Self::AtParameter { path: inner, error } => Self::AtParameter {
+                path: format!("{}.{}", path, inner),
+                error,
+            },
+            error => error.at(path),
+        }
+    }
+}
+
+impl fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSignature(signature) => {
+                write!(f, "Invalid function signature {}", signature)
+            }
+            Self::ParameterCount { expected, actual } => write!(
+                f,
+                "Number of parameters don't match, expected {} got {}",
+                expected, actual
+            ),
+            Self::UnsupportedType(kind) => write!(f, "Unsupported format {}", kind),
+            Self::InvalidTypeSize { kind, reason } => {
+                write!(f, "The numerical part in {} {}", kind, reason)
+            }
+            Self::InvalidValue {
+                kind,
+                value,
+                reason,
+            } => write!(
+                f,
+                "{}: {} in {} for {}",
+                *ERROR_PARSING, reason, value, kind
+            ),
+            Self::TypeMismatch { kind, value } => {
+                write!(f, "{}: {} is not a valid {}", *ERROR_PARSING, value, kind)
+            }
+            Self::InvalidList {
+                list,
+                position,
+                reason,
+            } => write!(
+                f,
+                "{}: {} at position {} in {}",
+                *ERROR_PARSING, reason, position, list
+            ),
+            Self::OutOfRange { kind, value } => {
+                write!(f, "The value {} is out of range for {}", value, kind)
+            }
+            Self::NotAnInteger(value) => write!(f, "The value {} is not an integer", value),
+            Self::BytesLength {
+                kind,
+                value,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "The value {} has {} bytes, expected exactly {} for {}",
+                value, actual, expected, kind
+            ),
+            Self::LengthMismatch {
+                kind,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Expected {} elements for {}, got {}",
+                expected, kind, actual
+            ),
+            Self::MissingComponent { name, value } => {
+                write!(f, "{}: missing {} in {}", *ERROR_PARSING, name, value)
+            }
+            Self::UnnamedComponents(kind) => write!(
+                f,
+                "{}: {} components must be named to use a JSON object",
+                *ERROR_PARSING, kind
+            ),
+            Self::InvalidChecksum { address, expected } => write!(
+                f,
+                "Invalid address checksum for {}, expected {}",
+                address, expected
+            ),
+            Self::InvalidOutput(error) => {
+                write!(f, "Output doesn't match the return signature: {}", error)
+            }
+            Self::AtParameter { path, error } => write!(f, "{}: {}", path, error),
+        }
+    }
+}
+
+impl std::error::Error for BridgeError {}
+
+/// Turns a `BridgeError` into the panic message of a contract method.
+#[cfg(any(test, feature = "contract"))]
+pub(crate) trait UnwrapOrPanic<T> {
+    fn unwrap_or_panic(self) -> T;
+}
+
+#[cfg(any(test, feature = "contract"))]
+impl<T> UnwrapOrPanic<T> for Result<T> {
+    fn unwrap_or_panic(self) -> T {
+        self.unwrap_or_else(|error| panic!("{}", error))
+    }
+}
@@ -0,0 +1,541 @@
+use crate::aurora::{ResultLog, SubmitResult, TransactionStatus};
+use crate::error::{BridgeError, Result};
+use crate::utils::{self, Selector};
+use aurora_engine_types::U256;
+use ethabi::{ParamType, Token};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+    serde_json::{json, Value},
+};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LogOutput {
+    pub address: String,
+    pub topics: Vec<String>,
+    pub data: String,
+}
+
+/// JSON view of a successful Aurora `SubmitResult`, with hex fields `0x` prefixed.
+/// `decoded` holds the output decoded with the return signature, if one was given, and
+/// `decode_error` why it couldn't be decoded with it.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CallOutput {
+    pub status: String,
+    pub gas_used: u64,
+    pub output: String,
+    pub logs: Vec<LogOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decode_error: Option<String>,
+}
+
+/// JSON view of a successful Aurora `view` call, with the output `0x` prefixed.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ViewOutput {
+    pub status: String,
+    pub output: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decode_error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DeployOutput {
+    pub status: String,
+    pub gas_used: u64,
+    /// Address of the deployed contract, `0x` hex.
+    pub address: String,
+    pub logs: Vec<LogOutput>,
+}
+
+/// Outcome of one call of a `multicall`. Multicall3 doesn't report the gas of each call.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MulticallResult {
+    pub success: bool,
+    pub output: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MulticallOutput {
+    pub status: String,
+    pub gas_used: u64,
+    pub results: Vec<MulticallResult>,
+    pub logs: Vec<LogOutput>,
+    /// Why the output couldn't be decoded as the `aggregate3` results, if so.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decode_error: Option<String>,
+}
+
+/// Outcome of one call of a batch. Failed EVM calls are reported with their revert reason
+/// instead of failing the batch, and calls that didn't run are `Skipped`.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CallReport {
+    pub status: String,
+    pub gas_used: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CallReport {
+    pub fn succeeded(&self) -> bool {
+        self.status == "Succeed"
+    }
+
+    #[cfg(feature = "contract")]
+    pub(crate) fn failed(error: &str) -> Self {
+        Self {
+            status: "Failed".to_string(),
+            gas_used: 0,
+            output: None,
+            error: Some(error.to_string()),
+        }
+    }
+
+    #[cfg(feature = "contract")]
+    pub(crate) fn skipped() -> Self {
+        Self {
+            status: "Skipped".to_string(),
+            gas_used: 0,
+            output: None,
+            error: None,
+        }
+    }
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+impl From<ResultLog> for LogOutput {
+    fn from(log: ResultLog) -> Self {
+        Self {
+            address: to_hex(&log.address),
+            topics: log.topics.iter().map(|topic| to_hex(topic)).collect(),
+            data: to_hex(&log.data),
+        }
+    }
+}
+
+/// Parses a return signature such as `(uint256)` or `(bool,address,uint256[])`, with optional
+/// names. A single type without parentheses is also accepted.
+pub(crate) fn return_types(returns: &str) -> Result<Vec<ParamType>> {
+    Ok(match utils::param_type(&utils::canonical_type(returns)?)? {
+        ParamType::Tuple(types) => types,
+        param_type => vec![param_type],
+    })
+}
+
+fn to_signed_decimal(value: U256) -> String {
+    if value.bit(255) {
+        format!("-{}", (!value).overflowing_add(U256::one()).0)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Converts a decoded token into JSON: decimal strings for integers, `0x` hex for
+/// addresses and bytes, and nested arrays for arrays and tuples.
+fn token_to_json(token: Token) -> Value {
+    match token {
+        Token::Address(address) => json!(to_hex(address.as_bytes())),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => json!(to_hex(&bytes)),
+        Token::Int(value) => json!(to_signed_decimal(value)),
+        Token::Uint(value) => json!(value.to_string()),
+        Token::Bool(value) => json!(value),
+        Token::String(value) => json!(value),
+        Token::FixedArray(tokens) | Token::Array(tokens) | Token::Tuple(tokens) => {
+            Value::Array(tokens.into_iter().map(token_to_json).collect())
+        }
+    }
+}
+
+pub(crate) fn decode_output(types: &[ParamType], output: &[u8]) -> Result<Vec<Value>> {
+    Ok(ethabi::decode(types, output)
+        .map_err(|e| BridgeError::InvalidOutput(e.to_string()))?
+        .into_iter()
+        .map(token_to_json)
+        .collect())
+}
+
+/// Selector of the standard `Error(string)` revert.
+const ERROR_SELECTOR: Selector = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of the `Panic(uint256)` revert emitted by failed asserts, overflows, etc.
+const PANIC_SELECTOR: Selector = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Parameter types of an error signature such as `InsufficientBalance(uint256,uint256)`.
+pub(crate) fn error_types(signature: &str) -> Result<Vec<ParamType>> {
+    let open = signature
+        .find('(')
+        .ok_or_else(|| BridgeError::InvalidSignature(signature.to_string()))?;
+    return_types(&signature[open..])
+}
+
+fn panic_description(code: U256) -> &'static str {
+    match code.low_u64() {
+        0x00 => "generic compiler panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on an empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to a zero-initialized internal function",
+        _ => "unknown panic code",
+    }
+}
+
+fn display_value(value: Value) -> String {
+    match value {
+        Value::String(value) => value,
+        value => value.to_string(),
+    }
+}
+
+/// Decodes a revert payload into a readable reason: `Error(string)` messages, `Panic(uint256)`
+/// codes and the custom errors known to `custom_error`, which maps a selector to its signature.
+pub(crate) fn revert_reason(
+    data: &[u8],
+    custom_error: impl Fn(&Selector) -> Option<String>,
+) -> String {
+    if data.len() < 4 {
+        return format!("unknown error {}", to_hex(data));
+    }
+    let mut selector = Selector::default();
+    selector.copy_from_slice(&data[..4]);
+    let payload = &data[4..];
+
+    let decoded = match selector {
+        ERROR_SELECTOR => ethabi::decode(&[ParamType::String], payload)
+            .ok()
+            .and_then(|mut tokens| tokens.pop())
+            .and_then(Token::into_string),
+        PANIC_SELECTOR => ethabi::decode(&[ParamType::Uint(256)], payload)
+            .ok()
+            .and_then(|mut tokens| tokens.pop())
+            .and_then(Token::into_uint)
+            .map(|code| format!("Panic(0x{:02x}): {}", code, panic_description(code))),
+        selector => custom_error(&selector).and_then(|signature| {
+            let name = &signature[..signature.find('(').unwrap_or(signature.len())];
+            error_types(&signature)
+                .ok()
+                .and_then(|types| ethabi::decode(&types, payload).ok())
+                .map(|tokens| {
+                    let values = tokens
+                        .into_iter()
+                        .map(|token| display_value(token_to_json(token)))
+                        .collect::<Vec<_>>();
+                    format!("{}({})", name, values.join(", "))
+                })
+        }),
+    };
+
+    decoded.unwrap_or_else(|| format!("unknown error {}", to_hex(data)))
+}
+
+fn status_name(status: &TransactionStatus) -> &'static str {
+    match status {
+        TransactionStatus::Succeed(_) => "Succeed",
+        TransactionStatus::Revert(_) => "Revert",
+        TransactionStatus::OutOfGas => "OutOfGas",
+        TransactionStatus::OutOfFund => "OutOfFund",
+        TransactionStatus::OutOfOffset => "OutOfOffset",
+        TransactionStatus::CallTooDeep => "CallTooDeep",
+    }
+}
+
+/// Reports the result of an Aurora call without failing on EVM errors, with revert reasons
+/// decoded with the custom errors known to `custom_error`.
+pub(crate) fn call_report(
+    result: SubmitResult,
+    custom_error: impl Fn(&Selector) -> Option<String>,
+) -> CallReport {
+    let status = status_name(&result.status).to_string();
+    let (output, error) = match result.status {
+        TransactionStatus::Succeed(output) => (Some(to_hex(&output)), None),
+        TransactionStatus::Revert(data) if data.is_empty() => (None, None),
+        TransactionStatus::Revert(data) => (None, Some(revert_reason(&data, custom_error))),
+        _ => (None, None),
+    };
+
+    CallReport {
+        status,
+        gas_used: result.gas_used,
+        output,
+        error,
+    }
+}
+
+/// Decodes the `(bool success, bytes returnData)[]` output of Multicall3's `aggregate3`, with
+/// revert reasons decoded with the custom errors known to `custom_error`.
+pub(crate) fn aggregate3_results(
+    output: &[u8],
+    custom_error: impl Fn(&Selector) -> Option<String>,
+) -> Result<Vec<MulticallResult>> {
+    let result_type = ParamType::Tuple(vec![ParamType::Bool, ParamType::Bytes]);
+    let results = match ethabi::decode(&[ParamType::Array(Box::new(result_type))], output)
+        .map_err(|e| BridgeError::InvalidOutput(e.to_string()))?
+        .pop()
+    {
+        Some(Token::Array(results)) => results,
+        _ => return Err(BridgeError::InvalidOutput("missing results".to_string())),
+    };
+
+    results
+        .into_iter()
+        .map(|result| match result {
+            Token::Tuple(fields) => match fields.as_slice() {
+                [Token::Bool(success), Token::Bytes(data)] => Ok(MulticallResult {
+                    success: *success,
+                    output: to_hex(data),
+                    error: (!success && !data.is_empty())
+                        .then(|| revert_reason(data, &custom_error)),
+                }),
+                _ => Err(BridgeError::InvalidOutput(format!("{:?}", fields))),
+            },
+            result => Err(BridgeError::InvalidOutput(format!("{:?}", result))),
+        })
+        .collect()
+}
+
+/// Output of a successful EVM call, panicking with the decoded revert reason if it failed.
+#[cfg(feature = "contract")]
+fn succeeded(
+    status: TransactionStatus,
+    custom_error: impl Fn(&Selector) -> Option<String>,
+) -> Vec<u8> {
+    match status {
+        TransactionStatus::Succeed(output) => output,
+        TransactionStatus::Revert(data) if data.is_empty() => panic!("Aurora call reverted"),
+        TransactionStatus::Revert(data) => panic!(
+            "Aurora call reverted: {}",
+            revert_reason(&data, custom_error)
+        ),
+        TransactionStatus::OutOfGas => panic!("Aurora call ran out of gas"),
+        TransactionStatus::OutOfFund => panic!("Aurora call ran out of funds"),
+        TransactionStatus::OutOfOffset => panic!("Aurora call ran out of offset"),
+        TransactionStatus::CallTooDeep => panic!("Aurora call is too deep"),
+    }
+}
+
+/// Output decoded with `returns`, or why it couldn't be. The EVM call already succeeded, so
+/// an output that doesn't match `returns` is reported instead of failing the receipt.
+#[cfg(feature = "contract")]
+fn decoded(returns: Option<&str>, output: &[u8]) -> (Option<Vec<Value>>, Option<String>) {
+    match returns
+        .map(|returns| return_types(returns).and_then(|types| decode_output(&types, output)))
+    {
+        Some(Ok(decoded)) => (Some(decoded), None),
+        Some(Err(error)) => (None, Some(error.to_string())),
+        None => (None, None),
+    }
+}
+
+/// Turns the result of an Aurora call into a `CallOutput`, panicking if the EVM call failed.
+/// The output is decoded when a return signature is given, and revert reasons are decoded
+/// with the custom errors known to `custom_error`.
+#[cfg(feature = "contract")]
+pub(crate) fn call_output(
+    result: SubmitResult,
+    returns: Option<&str>,
+    custom_error: impl Fn(&Selector) -> Option<String>,
+) -> CallOutput {
+    let output = succeeded(result.status, custom_error);
+    let (decoded, decode_error) = decoded(returns, &output);
+
+    CallOutput {
+        status: "Succeed".to_string(),
+        gas_used: result.gas_used,
+        decoded,
+        decode_error,
+        output: to_hex(&output),
+        logs: result.logs.into_iter().map(Into::into).collect(),
+    }
+}
+
+/// Same as `call_output` for a Multicall3 `aggregate3` call, with the result of each call.
+#[cfg(feature = "contract")]
+pub(crate) fn multicall_output(
+    result: SubmitResult,
+    custom_error: impl Fn(&Selector) -> Option<String>,
+) -> MulticallOutput {
+    let output = succeeded(result.status, &custom_error);
+    // The calls already happened, an unexpected output must not fail the receipt
+    let (results, decode_error) = match aggregate3_results(&output, custom_error) {
+        Ok(results) => (results, None),
+        Err(error) => (vec![], Some(error.to_string())),
+    };
+
+    MulticallOutput {
+        status: "Succeed".to_string(),
+        gas_used: result.gas_used,
+        results,
+        logs: result.logs.into_iter().map(Into::into).collect(),
+        decode_error,
+    }
+}
+
+/// Same as `call_output` for Aurora's `deploy_code`, whose output is the new contract address.
+#[cfg(feature = "contract")]
+pub(crate) fn deploy_output(
+    result: SubmitResult,
+    custom_error: impl Fn(&Selector) -> Option<String>,
+) -> DeployOutput {
+    let output = succeeded(result.status, custom_error);
+    assert_eq!(output.len(), 20, "Aurora deploy didn't return an address");
+
+    DeployOutput {
+        status: "Succeed".to_string(),
+        gas_used: result.gas_used,
+        address: to_hex(&output),
+        logs: result.logs.into_iter().map(Into::into).collect(),
+    }
+}
+
+/// Same as `call_output` for the `TransactionStatus` returned by Aurora's `view`.
+#[cfg(feature = "contract")]
+pub(crate) fn view_output(
+    status: TransactionStatus,
+    returns: Option<&str>,
+    custom_error: impl Fn(&Selector) -> Option<String>,
+) -> ViewOutput {
+    let output = succeeded(status, custom_error);
+    let (decoded, decode_error) = decoded(returns, &output);
+
+    ViewOutput {
+        status: "Succeed".to_string(),
+        decoded,
+        decode_error,
+        output: to_hex(&output),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::UnwrapOrPanic;
+    use ethabi::ethereum_types::Address;
+
+    #[test]
+    fn test_return_types() {
+        assert_eq!(
+            return_types("(uint256)").unwrap(),
+            vec![ParamType::Uint(256)]
+        );
+        assert_eq!(return_types("uint256").unwrap(), vec![ParamType::Uint(256)]);
+        assert_eq!(
+            return_types("(uint balance)").unwrap(),
+            vec![ParamType::Uint(256)]
+        );
+        assert_eq!(
+            return_types("(bool,address,uint256[])").unwrap(),
+            vec![
+                ParamType::Bool,
+                ParamType::Address,
+                ParamType::Array(Box::new(ParamType::Uint(256)))
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unsupported format foo")]
+    fn test_invalid_return_types() {
+        return_types("(foo,bool)").unwrap_or_panic();
+    }
+
+    #[test]
+    fn test_decode_output() {
+        let output = ethabi::encode(&[
+            Token::Bool(true),
+            Token::Address(Address::from([0xab; 20])),
+            Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+            Token::Int(!U256::from(4)),
+            Token::Bytes(vec![0x12, 0x34]),
+            Token::Tuple(vec![Token::String("hello".to_string())]),
+        ]);
+
+        assert_eq!(
+            decode_output(
+                &return_types("(bool,address,uint256[],int8,bytes,(string))").unwrap(),
+                &output
+            )
+            .unwrap(),
+            vec![
+                json!(true),
+                json!(format!("0x{}", "ab".repeat(20))),
+                json!(["1", "2"]),
+                json!("-5"),
+                json!("0x1234"),
+                json!(["hello"]),
+            ]
+        );
+    }
+
+    fn no_custom_error(_: &Selector) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_revert_reason_error() {
+        let data = [
+            ERROR_SELECTOR.to_vec(),
+            ethabi::encode(&[Token::String("Not enough funds".to_string())]),
+        ]
+        .concat();
+        assert_eq!(revert_reason(&data, no_custom_error), "Not enough funds");
+    }
+
+    #[test]
+    fn test_revert_reason_panic() {
+        let data = [
+            PANIC_SELECTOR.to_vec(),
+            ethabi::encode(&[Token::Uint(0x11.into())]),
+        ]
+        .concat();
+        assert_eq!(
+            revert_reason(&data, no_custom_error),
+            "Panic(0x11): arithmetic overflow or underflow"
+        );
+    }
+
+    #[test]
+    fn test_revert_reason_custom_error() {
+        let signature = "InsufficientBalance(uint256,uint256)";
+        let data = [
+            utils::get_selector(signature).unwrap().to_vec(),
+            ethabi::encode(&[Token::Uint(100.into()), Token::Uint(200.into())]),
+        ]
+        .concat();
+
+        assert_eq!(
+            revert_reason(&data, |_| Some(signature.to_string())),
+            "InsufficientBalance(100, 200)"
+        );
+        assert_eq!(
+            revert_reason(&data, no_custom_error),
+            format!("unknown error {}", to_hex(&data))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Output doesn't match the return signature")]
+    fn test_decode_output_mismatch() {
+        decode_output(&return_types("(uint256)").unwrap(), &[0x01]).unwrap_or_panic();
+    }
+}
@@ -0,0 +1,396 @@
+use crate::aurora::RawAddress;
+use crate::error::UnwrapOrPanic;
+use crate::utils::{self, get_selector, Selector};
+use crate::{ContractBridge, ContractBridgeExt};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    env, near_bindgen,
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Roles that can be granted by the owner (or by an admin, for non-admin roles).
+/// - `Admin` can call any Aurora contract and manage operator and viewer grants.
+/// - `Operator` can only call the Aurora contracts and selectors in its allowlist.
+/// - `Viewer` can only simulate calls with `view_call`, which the other roles can also do.
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Role {
+    Admin,
+    Operator,
+    Viewer,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Grant {
+    pub role: Role,
+    pub allowed: HashMap<RawAddress, HashSet<Selector>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GrantView {
+    pub role: Role,
+    /// Aurora contract address (hex) to allowed selectors (hex).
+    pub allowed: HashMap<String, Vec<String>>,
+}
+
+impl From<&Grant> for GrantView {
+    fn from(grant: &Grant) -> Self {
+        Self {
+            role: grant.role,
+            allowed: grant
+                .allowed
+                .iter()
+                .map(|(address, selectors)| {
+                    let mut selectors = selectors.iter().map(hex::encode).collect::<Vec<_>>();
+                    selectors.sort();
+                    (hex::encode(address), selectors)
+                })
+                .collect(),
+        }
+    }
+}
+
+#[near_bindgen]
+impl ContractBridge {
+    /// Grants `role` to `account_id`, keeping its allowlist if it already had a grant.
+    pub fn grant_role(&mut self, account_id: AccountId, role: Role) {
+        self.assert_can_manage(&account_id, role);
+        let grant = match self.grants.get(&account_id) {
+            Some(grant) => Grant { role, ..grant },
+            None => Grant {
+                role,
+                allowed: HashMap::new(),
+            },
+        };
+        self.grants.insert(&account_id, &grant);
+    }
+
+    pub fn revoke_role(&mut self, account_id: AccountId) {
+        let grant = self.grants.get(&account_id).expect("Account has no role");
+        self.assert_can_manage(&account_id, grant.role);
+        self.grants.remove(&account_id);
+    }
+
+    /// Allows `account_id` to call `functions` (e.g. `"transfer(address,uint256)"`) on `aurora_address`.
+    pub fn allow_call(
+        &mut self,
+        account_id: AccountId,
+        aurora_address: String,
+        functions: Vec<String>,
+    ) {
+        let mut grant = self.grants.get(&account_id).expect("Account has no role");
+        self.assert_can_manage(&account_id, grant.role);
+        let aurora_contract = utils::from_string_to_address(&aurora_address).unwrap_or_panic();
+
+        grant.allowed.entry(aurora_contract.0).or_default().extend(
+            functions
+                .iter()
+                .map(|function| get_selector(function).unwrap_or_panic()),
+        );
+        self.grants.insert(&account_id, &grant);
+    }
+
+    /// Removes `functions` from the allowlist of `account_id` on `aurora_address`,
+    /// or the whole Aurora contract if no functions are given.
+    pub fn disallow_call(
+        &mut self,
+        account_id: AccountId,
+        aurora_address: String,
+        functions: Option<Vec<String>>,
+    ) {
+        let mut grant = self.grants.get(&account_id).expect("Account has no role");
+        self.assert_can_manage(&account_id, grant.role);
+        let aurora_contract = utils::from_string_to_address(&aurora_address).unwrap_or_panic();
+
+        match functions {
+            Some(functions) => {
+                if let Some(selectors) = grant.allowed.get_mut(&aurora_contract.0) {
+                    for function in functions {
+                        selectors.remove(&get_selector(&function).unwrap_or_panic());
+                    }
+                    if selectors.is_empty() {
+                        grant.allowed.remove(&aurora_contract.0);
+                    }
+                }
+            }
+            None => {
+                grant.allowed.remove(&aurora_contract.0);
+            }
+        }
+        self.grants.insert(&account_id, &grant);
+    }
+
+    pub fn get_role(&self, account_id: AccountId) -> Option<Role> {
+        self.grants.get(&account_id).map(|grant| grant.role)
+    }
+
+    pub fn get_grant(&self, account_id: AccountId) -> Option<GrantView> {
+        self.grants.get(&account_id).map(|grant| (&grant).into())
+    }
+
+    pub fn get_grants(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(AccountId, GrantView)> {
+        self.grants
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(u64::MAX) as usize)
+            .map(|(account_id, grant)| (account_id, (&grant).into()))
+            .collect()
+    }
+}
+
+impl ContractBridge {
+    fn assert_can_manage(&self, account_id: &AccountId, role: Role) {
+        let predecessor = env::predecessor_account_id();
+        if predecessor == self.owner_id {
+            return;
+        }
+
+        let is_admin = self.get_role(predecessor) == Some(Role::Admin);
+        let target_is_admin = self.get_role(account_id.clone()) == Some(Role::Admin);
+        assert!(
+            is_admin && role != Role::Admin && !target_is_admin,
+            "Not allowed to manage the role of {}",
+            account_id
+        );
+    }
+
+    /// Panics unless the predecessor is allowed to call `function` on `aurora_contract`.
+    pub(crate) fn assert_can_call(&self, aurora_contract: &RawAddress, function: &str) {
+        let predecessor = env::predecessor_account_id();
+        if predecessor == self.owner_id {
+            return;
+        }
+
+        let allowed = match self.grants.get(&predecessor) {
+            Some(Grant {
+                role: Role::Admin, ..
+            }) => true,
+            Some(Grant {
+                role: Role::Operator,
+                allowed,
+            }) => matches!(
+                allowed.get(aurora_contract),
+                Some(selectors) if selectors.contains(&get_selector(function).unwrap_or_panic())
+            ),
+            _ => false,
+        };
+        assert!(
+            allowed,
+            "{} is not allowed to call {} on {}",
+            predecessor,
+            function,
+            hex::encode(aurora_contract)
+        );
+    }
+
+    fn is_owner_or_admin(&self, account_id: &AccountId) -> bool {
+        *account_id == self.owner_id || self.get_role(account_id.clone()) == Some(Role::Admin)
+    }
+
+    /// Panics unless the predecessor can spend the ETH balance of the bridge on Aurora, which
+    /// is reserved to the owner and admins.
+    pub(crate) fn assert_can_send_value(&self) {
+        let predecessor = env::predecessor_account_id();
+        assert!(
+            self.is_owner_or_admin(&predecessor),
+            "{} is not allowed to attach value to Aurora calls",
+            predecessor
+        );
+    }
+
+    /// Panics unless the predecessor can simulate calls with `view_call`, which is reserved to
+    /// the owner and the accounts with a role.
+    pub(crate) fn assert_can_view(&self) {
+        let predecessor = env::predecessor_account_id();
+        assert!(
+            predecessor == self.owner_id || self.grants.get(&predecessor).is_some(),
+            "{} is not allowed to make view calls",
+            predecessor
+        );
+    }
+
+    /// Panics unless the predecessor can deploy contracts from the bridge address, which is
+    /// reserved to the owner and admins.
+    pub(crate) fn assert_can_deploy(&self) {
+        let predecessor = env::predecessor_account_id();
+        assert!(
+            self.is_owner_or_admin(&predecessor),
+            "{} is not allowed to deploy Aurora contracts",
+            predecessor
+        );
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    const USN: &str = "a5c7fdbe2a8b814369e89eaed7ee52630fcb4c59";
+
+    fn setup() -> (VMContextBuilder, ContractBridge) {
+        let mut context = VMContextBuilder::new();
+        context
+            .current_account_id(accounts(0))
+            .predecessor_account_id(accounts(0));
+        testing_env!(context.build());
+        (context, ContractBridge::new(None, None))
+    }
+
+    fn address(input: &str) -> RawAddress {
+        utils::from_string_to_address(input).unwrap().0
+    }
+
+    #[test]
+    fn test_grant_and_allow() {
+        let (_, mut contract) = setup();
+        contract.grant_role(accounts(1), Role::Operator);
+        contract.allow_call(
+            accounts(1),
+            USN.to_string(),
+            vec!["transfer(address,uint256)".to_string()],
+        );
+
+        assert_eq!(contract.get_role(accounts(1)), Some(Role::Operator));
+        assert_eq!(
+            contract.get_grant(accounts(1)),
+            Some(GrantView {
+                role: Role::Operator,
+                allowed: vec![(USN.to_string(), vec!["a9059cbb".to_string()])]
+                    .into_iter()
+                    .collect(),
+            })
+        );
+        assert_eq!(contract.get_grants(None, None).len(), 1);
+
+        contract.disallow_call(accounts(1), USN.to_string(), None);
+        assert!(contract.get_grant(accounts(1)).unwrap().allowed.is_empty());
+
+        contract.revoke_role(accounts(1));
+        assert_eq!(contract.get_role(accounts(1)), None);
+    }
+
+    #[test]
+    fn test_operator_can_call_allowed() {
+        let (mut context, mut contract) = setup();
+        contract.grant_role(accounts(1), Role::Operator);
+        contract.allow_call(
+            accounts(1),
+            USN.to_string(),
+            vec!["transfer(address,uint256)".to_string()],
+        );
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.assert_can_call(&address(USN), "transfer(address,uint256)");
+    }
+
+    #[test]
+    #[should_panic(expected = "is not allowed to call approve(address,uint256)")]
+    fn test_operator_cannot_call_other_selector() {
+        let (mut context, mut contract) = setup();
+        contract.grant_role(accounts(1), Role::Operator);
+        contract.allow_call(
+            accounts(1),
+            USN.to_string(),
+            vec!["transfer(address,uint256)".to_string()],
+        );
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.assert_can_call(&address(USN), "approve(address,uint256)");
+    }
+
+    #[test]
+    #[should_panic(expected = "is not allowed to call")]
+    fn test_viewer_cannot_call() {
+        let (mut context, mut contract) = setup();
+        contract.grant_role(accounts(1), Role::Viewer);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.assert_can_call(&address(USN), "transfer(address,uint256)");
+    }
+
+    #[test]
+    fn test_roles_can_view() {
+        let (mut context, mut contract) = setup();
+        contract.assert_can_view();
+        for role in [Role::Admin, Role::Operator, Role::Viewer] {
+            contract.grant_role(accounts(1), role);
+
+            testing_env!(context.predecessor_account_id(accounts(1)).build());
+            contract.assert_can_view();
+            testing_env!(context.predecessor_account_id(accounts(0)).build());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "is not allowed to make view calls")]
+    fn test_no_role_cannot_view() {
+        let (mut context, contract) = setup();
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.assert_can_view();
+    }
+
+    #[test]
+    #[should_panic(expected = "is not allowed to attach value to Aurora calls")]
+    fn test_operator_cannot_send_value() {
+        let (mut context, mut contract) = setup();
+        contract.grant_role(accounts(1), Role::Operator);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.assert_can_send_value();
+    }
+
+    #[test]
+    #[should_panic(expected = "is not allowed to deploy Aurora contracts")]
+    fn test_operator_cannot_deploy() {
+        let (mut context, mut contract) = setup();
+        contract.grant_role(accounts(1), Role::Operator);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.assert_can_deploy();
+    }
+
+    #[test]
+    fn test_admin_can_manage_operators() {
+        let (mut context, mut contract) = setup();
+        contract.grant_role(accounts(1), Role::Admin);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.assert_can_call(&address(USN), "approve(address,uint256)");
+        contract.assert_can_send_value();
+        contract.grant_role(accounts(2), Role::Operator);
+        assert_eq!(contract.get_role(accounts(2)), Some(Role::Operator));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not allowed to manage the role of")]
+    fn test_admin_cannot_grant_admin() {
+        let (mut context, mut contract) = setup();
+        contract.grant_role(accounts(1), Role::Admin);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.grant_role(accounts(2), Role::Admin);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not allowed to manage the role of")]
+    fn test_operator_cannot_grant() {
+        let (mut context, mut contract) = setup();
+        contract.grant_role(accounts(1), Role::Operator);
+
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.grant_role(accounts(2), Role::Viewer);
+    }
+}
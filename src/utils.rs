@@ -1,14 +1,59 @@
-use aurora_engine_types::{H256, U256};
-use ethabi::{ethereum_types::Address, Token};
-use near_sdk::env;
-
-pub fn from_string_to_address(input: &str) -> Address {
+use crate::aurora::RawAddress;
+use crate::error::{BridgeError, Result};
+use aurora_engine_types::{types::RawU256, H256, U256};
+use ethabi::{
+    ethereum_types::{Address, FromDecStrErr},
+    ParamType, Token,
+};
+use near_sdk::{env, serde_json::Value};
+use std::convert::TryFrom;
+
+/// Parses a 20 bytes hex address, with or without the `0x` prefix.
+/// Mixed-case addresses must match their EIP-55 checksum.
+pub fn from_string_to_address(input: &str) -> Result<Address> {
+    let hex_address = strip_hex_prefix(input);
     let mut contract_u820 = [0u8; 20];
-    assert_eq!(
-        hex::decode_to_slice(input, &mut contract_u820 as &mut [u8]),
-        Ok(())
-    );
-    Address::from(contract_u820)
+    hex::decode_to_slice(hex_address, &mut contract_u820 as &mut [u8])
+        .map_err(|e| BridgeError::invalid_value("address", input, e))?;
+
+    let is_mixed_case = hex_address.chars().any(|c| c.is_ascii_lowercase())
+        && hex_address.chars().any(|c| c.is_ascii_uppercase());
+    if is_mixed_case {
+        let checksummed = to_checksum_address(&contract_u820);
+        if hex_address != checksummed {
+            return Err(BridgeError::InvalidChecksum {
+                address: input.to_string(),
+                expected: checksummed,
+            });
+        }
+    }
+    Ok(Address::from(contract_u820))
+}
+
+/// EIP-55 mixed-case checksum encoding of an address, without the `0x` prefix.
+pub(crate) fn to_checksum_address(address: &[u8; 20]) -> String {
+    let lowercase = hex::encode(address);
+    let hash = keccak(lowercase.as_bytes());
+
+    lowercase
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+fn strip_hex_prefix(value: &str) -> &str {
+    value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value)
 }
 
 #[inline]
@@ -16,18 +61,57 @@ pub fn keccak(input: &[u8]) -> H256 {
     H256::from_slice(&env::keccak256(input))
 }
 
-fn get_selector(str_selector: &str) -> Vec<u8> {
-    keccak(str_selector.as_bytes())[..4].to_vec()
+/// Address of a NEAR account on Aurora, the last 20 bytes of the keccak of its account id.
+pub(crate) fn near_account_to_address(account_id: &str) -> RawAddress {
+    let mut address = RawAddress::default();
+    address.copy_from_slice(&keccak(account_id.as_bytes())[12..]);
+    address
 }
 
-fn build_input(str_selector: &str, inputs: &[Token]) -> Vec<u8> {
-    let sel = get_selector(str_selector);
-    let inputs = ethabi::encode(inputs);
-    [sel.as_slice(), inputs.as_slice()].concat()
+/// Address of a contract deployed with CREATE2 by `factory`, the last 20 bytes of
+/// `keccak(0xff ++ factory ++ salt ++ init_code_hash)`.
+pub(crate) fn create2_address(
+    factory: &RawAddress,
+    salt: &[u8; 32],
+    init_code_hash: &[u8; 32],
+) -> RawAddress {
+    let mut address = RawAddress::default();
+    address.copy_from_slice(&keccak(&[&[0xff], &factory[..], salt, init_code_hash].concat())[12..]);
+    address
+}
+
+pub type Selector = [u8; 4];
+
+/// Signature of Multicall3's `aggregate3`, which makes `(target, allowFailure, callData)` calls.
+const AGGREGATE3: &str = "aggregate3((address,bool,bytes)[])";
+
+/// Encodes a Multicall3 `aggregate3` call of `calls`, each given as its target and input.
+/// Unless `allow_failure` is set, a failed call reverts all of them.
+pub(crate) fn aggregate3(calls: &[(RawAddress, Vec<u8>)], allow_failure: bool) -> Result<Vec<u8>> {
+    let calls = calls
+        .iter()
+        .map(|(target, input)| {
+            Token::Tuple(vec![
+                Token::Address(Address::from(*target)),
+                Token::Bool(allow_failure),
+                Token::Bytes(input.clone()),
+            ])
+        })
+        .collect();
+    build_input(AGGREGATE3, &[Token::Array(calls)])
+}
+
+/// Selector of a function signature, hashed in its canonical form.
+pub(crate) fn get_selector(str_selector: &str) -> Result<Selector> {
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&keccak(canonical_signature(str_selector)?.as_bytes())[..4]);
+    Ok(selector)
 }
 
-lazy_static! {
-    static ref ERROR_PARSING: &'static str = "Invalid input parameter";
+fn build_input(str_selector: &str, inputs: &[Token]) -> Result<Vec<u8>> {
+    let sel = get_selector(str_selector)?;
+    let inputs = ethabi::encode(inputs);
+    Ok([sel.as_slice(), inputs.as_slice()].concat())
 }
 
 fn get_numerical_part(parameter: &str) -> Option<usize> {
@@ -36,183 +120,1068 @@ fn get_numerical_part(parameter: &str) -> Option<usize> {
     numeric.parse::<usize>().ok()
 }
 
-fn numeric_sanity_check(parameter: &str, numerical: usize) {
-    match parameter {
-        int if int.contains("int") => {
-            assert!(
-                numerical <= 256,
-                "The numerical part in {} is greater than 256",
-                parameter,
-            );
-            assert_eq!(
-                numerical % 8,
-                0,
-                "The numerical part in {} is not divisible by 8",
-                parameter,
-            );
+fn numeric_sanity_check(parameter: &str, numerical: usize) -> Result<()> {
+    let reason = match parameter {
+        int if int.contains("int") => match numerical {
+            0 => "is lower than 8",
+            numerical if numerical > 256 => "is greater than 256",
+            numerical if numerical % 8 == 0 => return Ok(()),
+            _ => "is not divisible by 8",
+        },
+        bytes if bytes.contains("bytes") && !(0 < numerical && numerical <= 32) => {
+            "is out of bounds [1, 32]"
         }
-        bytes if bytes.contains("bytes") => assert!(
-            0 < numerical && numerical <= 32,
-            "The numerical part in bytes{} is out of bounds [1, 32]",
-            numerical
-        ),
-        _ => panic!("The type {} does not support numerical", parameter),
+        bytes if bytes.contains("bytes") => return Ok(()),
+        _ => return Err(BridgeError::UnsupportedType(parameter.to_string())),
+    };
+    Err(BridgeError::InvalidTypeSize {
+        kind: parameter.to_string(),
+        reason: reason.to_string(),
+    })
+}
+
+/// Splits `input` on the commas that are not nested inside parentheses or brackets.
+fn split_top_level(input: &str) -> Vec<&str> {
+    let mut depth: usize = 0;
+    let mut start: usize = 0;
+    let mut result = vec![];
+
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                result.push(&input[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !input.is_empty() {
+        result.push(&input[start..]);
+    }
+    result
+}
+
+/// Parses a Solidity type such as `uint256`, `address[2][]` or `(bool,bytes)` into an ethabi `ParamType`.
+pub(crate) fn param_type(type_part: &str) -> Result<ParamType> {
+    let unsupported = || BridgeError::UnsupportedType(type_part.to_string());
+    if let Some(inner) = type_part.strip_suffix("[]") {
+        Ok(ParamType::Array(Box::new(param_type(inner)?)))
+    } else if type_part.ends_with(']') {
+        let (inner, size) = fixed_array_type(type_part)?;
+        Ok(ParamType::FixedArray(Box::new(param_type(inner)?), size))
+    } else if let Some(inner) = type_part
+        .strip_prefix('(')
+        .and_then(|inner| inner.strip_suffix(')'))
+    {
+        Ok(ParamType::Tuple(
+            split_top_level(inner)
+                .into_iter()
+                .map(|component| param_type(component.trim()))
+                .collect::<Result<_>>()?,
+        ))
+    } else {
+        if let Some(numerical) = get_numerical_part(type_part) {
+            numeric_sanity_check(type_part, numerical)?;
+        }
+
+        let size = |prefix: &str| {
+            type_part[prefix.len()..]
+                .parse::<usize>()
+                .map_err(|_| unsupported())
+        };
+        Ok(match type_part {
+            "address" => ParamType::Address,
+            // An address followed by a selector
+            "function" => ParamType::FixedBytes(24),
+            "bool" => ParamType::Bool,
+            "bytes" => ParamType::Bytes,
+            "string" => ParamType::String,
+            "uint" => ParamType::Uint(256),
+            "int" => ParamType::Int(256),
+            uint if uint.starts_with("uint") => ParamType::Uint(size("uint")?),
+            int if int.starts_with("int") => ParamType::Int(size("int")?),
+            bytes if bytes.starts_with("bytes") => ParamType::FixedBytes(size("bytes")?),
+            &_ => return Err(unsupported()),
+        })
     }
 }
 
-fn solidity_lookup(type_part: &str, value: &str) -> Token {
-    match type_part {
-        uint if uint.contains("uint") => {
-            Token::Uint(U256::from_dec_str(value).expect(*ERROR_PARSING))
+fn out_of_range(type_part: &str, value: &str) -> BridgeError {
+    BridgeError::OutOfRange {
+        kind: type_part.to_string(),
+        value: value.to_string(),
+    }
+}
+
+/// Decimals of the supported ether units.
+fn unit_decimals(unit: &str) -> Option<usize> {
+    match unit.to_ascii_lowercase().as_str() {
+        "wei" => Some(0),
+        "gwei" => Some(9),
+        "ether" | "eth" => Some(18),
+        _ => None,
+    }
+}
+
+/// Parses a non-negative number given as decimal (`1500`), `0x` hex (`0x5dc`), scientific
+/// notation (`1.5e3`) and/or with an ether unit (`1.5 ether`, `3 gwei`). The result must be
+/// an exact integer, so fractional wei is rejected.
+fn parse_number(type_part: &str, value: &str) -> Result<U256> {
+    let value = value.trim();
+    let no_digits = || BridgeError::InvalidValue {
+        kind: type_part.to_string(),
+        value: value.to_string(),
+        reason: "no digits".to_string(),
+    };
+    if let Some(digits) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        if digits.is_empty() {
+            return Err(no_digits());
+        }
+        let digits = if digits.len() % 2 == 1 {
+            format!("0{}", digits)
+        } else {
+            digits.to_string()
+        };
+        let bytes =
+            hex::decode(digits).map_err(|e| BridgeError::invalid_value(type_part, value, e))?;
+        if bytes.len() > 32 {
+            return Err(out_of_range(type_part, value));
+        }
+        return Ok(U256::from_big_endian(&bytes));
+    }
+
+    // Split the unit, e.g. `1.5 ether` or `1.5ether`
+    let number = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let (number, mut exponent) = match unit_decimals(&value[number.len()..]) {
+        Some(decimals) => (number.trim_end(), decimals as i64),
+        None => (value, 0),
+    };
+
+    let scientific = number.find(['e', 'E']).and_then(|e| {
+        let power = number[e + 1..].parse::<i64>().ok()?;
+        Some((&number[..e], power))
+    });
+    let not_an_integer = || BridgeError::NotAnInteger(value.to_string());
+    let number = match scientific {
+        Some((mantissa, power)) => {
+            exponent = exponent.checked_add(power).ok_or_else(|| {
+                if power > 0 {
+                    out_of_range(type_part, value)
+                } else {
+                    not_an_integer()
+                }
+            })?;
+            mantissa
         }
-        int if int.contains("int") => Token::Int(U256::from_dec_str(value).expect(*ERROR_PARSING)),
-        "address" => {
-            let mut addr = [0u8; 20];
-            hex::decode_to_slice(value, &mut addr as &mut [u8]).expect(*ERROR_PARSING);
-            Token::Address(Address::from(addr))
+        None => number,
+    };
+    let (integer, fraction) = match number.split_once('.') {
+        Some((integer, fraction)) => (integer, fraction),
+        None => (number, ""),
+    };
+    if integer.is_empty() && fraction.is_empty() {
+        return Err(no_digits());
+    }
+    if !integer
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return Err(BridgeError::invalid_value(
+            type_part,
+            value,
+            FromDecStrErr::InvalidCharacter,
+        ));
+    }
+    exponent = i64::try_from(fraction.len())
+        .ok()
+        .and_then(|decimals| exponent.checked_sub(decimals))
+        .ok_or_else(not_an_integer)?;
+
+    let mut digits = format!("{}{}", integer, fraction);
+    if exponent < 0 {
+        // A shift wider than `usize` (e.g. on wasm32) cuts all the digits
+        let cut = usize::try_from(exponent.unsigned_abs())
+            .map_or(0, |shift| digits.len().saturating_sub(shift));
+        if !digits[cut..].chars().all(|c| c == '0') {
+            return Err(not_an_integer());
         }
-        "bool" => Token::Bool(value.parse::<bool>().expect(*ERROR_PARSING)),
-        "bytes" => Token::Bytes(hex::decode(value).expect(*ERROR_PARSING)),
+        digits.truncate(cut);
+        exponent = 0;
+    }
+    // All the digits were zeros cut by the exponent, e.g. `0e-3`
+    if digits.is_empty() {
+        digits.push('0');
+    }
+
+    let number =
+        U256::from_dec_str(&digits).map_err(|e| BridgeError::invalid_value(type_part, value, e))?;
+    U256::from(10)
+        .checked_pow(U256::from(exponent))
+        .and_then(|scale| number.checked_mul(scale))
+        .ok_or_else(|| out_of_range(type_part, value))
+}
+
+/// Parses an unsigned integer, checking it fits in the declared width.
+fn parse_uint(type_part: &str, value: &str) -> Result<U256> {
+    let bits = get_numerical_part(type_part).unwrap_or(256);
+    let number = parse_number(type_part, value)?;
+    if bits < 256 && !(number >> bits).is_zero() {
+        return Err(out_of_range(type_part, value));
+    }
+    Ok(number)
+}
+
+/// Parses a 32 bytes hex value, such as a CREATE2 salt or an init code hash.
+pub(crate) fn parse_bytes32(value: &str) -> Result<[u8; 32]> {
+    let decoded = hex::decode(strip_hex_prefix(value))
+        .map_err(|e| BridgeError::invalid_value("bytes32", value, e))?;
+    let mut bytes = [0u8; 32];
+    if decoded.len() != bytes.len() {
+        return Err(BridgeError::BytesLength {
+            kind: "bytes32".to_string(),
+            value: value.to_string(),
+            expected: bytes.len(),
+            actual: decoded.len(),
+        });
+    }
+    bytes.copy_from_slice(&decoded);
+    Ok(bytes)
+}
+
+/// Parses an amount of wei attached to a call, e.g. `1000`, `0x3e8` or `1.5 ether`, into the
+/// big-endian representation used by Aurora.
+pub(crate) fn parse_wei(value: &str) -> Result<RawU256> {
+    let mut wei = RawU256::default();
+    parse_uint("uint256", value)?.to_big_endian(&mut wei);
+    Ok(wei)
+}
+
+/// Parses a signed integer, checking it fits in the declared width, and encodes it as
+/// 256 bits two's complement.
+fn parse_int(type_part: &str, value: &str) -> Result<U256> {
+    let bits = get_numerical_part(type_part).unwrap_or(256);
+    let (negative, magnitude) = match value.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, value),
+    };
+    let magnitude = parse_number(type_part, magnitude)?;
+
+    // The range is [-2^(bits - 1), 2^(bits - 1) - 1]
+    let limit = U256::one() << (bits - 1);
+    if (negative && magnitude > limit) || (!negative && magnitude >= limit) {
+        return Err(out_of_range(type_part, value));
+    }
+
+    Ok(if negative {
+        (!magnitude).overflowing_add(U256::one()).0
+    } else {
+        magnitude
+    })
+}
+
+/// Parses an external function reference given as `address:selector` (e.g.
+/// `0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed:a9059cbb`) or `address:signature` (e.g.
+/// `0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed:transfer(address,uint256)`) into the 24 bytes
+/// ABI representation, the address followed by the selector.
+fn parse_function(value: &str) -> Result<Vec<u8>> {
+    let (address, function) = value
+        .split_once(':')
+        .ok_or_else(|| BridgeError::InvalidValue {
+            kind: "function".to_string(),
+            value: value.to_string(),
+            reason: "function must be address:selector or address:signature".to_string(),
+        })?;
+    let address = from_string_to_address(address.trim())?;
+    let function = function.trim();
+
+    let selector = if function.contains('(') {
+        get_selector(function)?
+    } else {
+        let mut selector = Selector::default();
+        hex::decode_to_slice(strip_hex_prefix(function), &mut selector as &mut [u8])
+            .map_err(|e| BridgeError::invalid_value("function", value, e))?;
+        selector
+    };
+    Ok([address.as_bytes(), &selector].concat())
+}
+
+fn solidity_lookup(type_part: &str, value: &str) -> Result<Token> {
+    let invalid = |e| BridgeError::invalid_value(type_part, value, e);
+    Ok(match type_part {
+        uint if uint.contains("uint") => Token::Uint(parse_uint(type_part, value)?),
+        int if int.contains("int") => Token::Int(parse_int(type_part, value)?),
+        "address" => Token::Address(from_string_to_address(value)?),
+        "bool" => Token::Bool(
+            value
+                .parse::<bool>()
+                .map_err(|e| BridgeError::invalid_value(type_part, value, e))?,
+        ),
+        "bytes" => Token::Bytes(hex::decode(strip_hex_prefix(value)).map_err(invalid)?),
         "string" => Token::String(value.to_string()),
         bytes if bytes.contains("bytes") => {
-            Token::FixedBytes(hex::decode(value).expect(*ERROR_PARSING))
+            let size = get_numerical_part(bytes).unwrap_or(32);
+            let fixed_bytes = hex::decode(strip_hex_prefix(value)).map_err(invalid)?;
+            if fixed_bytes.len() != size {
+                return Err(BridgeError::BytesLength {
+                    kind: type_part.to_string(),
+                    value: value.to_string(),
+                    expected: size,
+                    actual: fixed_bytes.len(),
+                });
+            }
+            Token::FixedBytes(fixed_bytes)
         }
-        "function" => {
-            let bytes24 = value.as_bytes().to_vec();
-            assert_eq!(bytes24.len(), 24, "function input parameter size incorrect");
-            Token::Bytes(bytes24)
+        "function" => Token::FixedBytes(parse_function(value)?),
+        &_ => return Err(BridgeError::UnsupportedType(type_part.to_string())),
+    })
+}
+
+/// An element of an array or tuple value.
+#[derive(Debug, PartialEq, Eq)]
+enum Element {
+    /// A quoted string, with its escapes resolved
+    Quoted(String),
+    /// A scalar or a nested array or tuple, as written
+    Raw(String),
+}
+
+fn invalid_list(list: &str, position: usize, reason: &str) -> BridgeError {
+    BridgeError::InvalidList {
+        list: list.to_string(),
+        position,
+        reason: reason.to_string(),
+    }
+}
+
+/// Reads the quoted string starting at `start`, returning it unescaped and the position after
+/// its closing quote. Supports the JSON escapes.
+fn read_quoted(list: &str, start: usize) -> Result<(String, usize)> {
+    let mut result = String::new();
+    let mut chars = list[start + 1..].char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((result, start + 1 + i + 1)),
+            '\\' => {
+                let escaped = match chars.next() {
+                    Some((_, '"')) => '"',
+                    Some((_, '\\')) => '\\',
+                    Some((_, '/')) => '/',
+                    Some((_, 'n')) => '\n',
+                    Some((_, 't')) => '\t',
+                    Some((_, 'r')) => '\r',
+                    Some((_, 'u')) => {
+                        let code = chars.by_ref().take(4).map(|(_, c)| c).collect::<String>();
+                        u32::from_str_radix(&code, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| {
+                                invalid_list(list, start + 1 + i, "invalid unicode escape")
+                            })?
+                    }
+                    _ => return Err(invalid_list(list, start + 1 + i, "invalid escape")),
+                };
+                result.push(escaped);
+            }
+            c => result.push(c),
         }
-        &_ => panic!("Unsupported format {}", type_part),
     }
+    Err(invalid_list(list, start, "unterminated string"))
 }
 
-fn group_array(array: &str) -> Vec<String> {
-    if !array.starts_with("[[") {
-        array
-            .split([' ', '[', ',', ']'])
-            .into_iter()
-            .filter(|char| !char.is_empty())
-            .map(Into::into)
-            .collect::<Vec<_>>()
-    } else {
-        let mut start: usize = 0;
-        let mut acc: usize = 0;
-        let mut result = vec![];
-
-        for (i, c) in array[0..array.len() - 1]
-            .chars()
-            .into_iter()
-            .enumerate()
-            .skip(1)
-        {
-            if c.to_string() == "[" {
-                if acc == 0 {
-                    start = i;
-                }
-                acc += 1;
-            } else if c.to_string() == "]" {
-                acc -= 1;
-                if acc == 0 {
-                    result.push(array[start..=i].to_string());
+/// Returns the position after the bracket or parenthesis closing the one at `start`,
+/// skipping quoted strings.
+fn skip_group(list: &str, start: usize) -> Result<usize> {
+    let mut depth: usize = 0;
+    let mut i = start;
+
+    while i < list.len() {
+        match list.as_bytes()[i] {
+            b'"' => {
+                i = read_quoted(list, i)?.1;
+                continue;
+            }
+            b'[' | b'(' => depth += 1,
+            b']' | b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(i + 1);
                 }
             }
+            _ => {}
         }
-        result
+        i += 1;
     }
+    Err(invalid_list(list, start, "unbalanced bracket"))
 }
 
-// [[1,2],[2,3]]
-fn tokenize(parameter_type: &str, parameter_value: &str) -> Token {
-    if parameter_type.ends_with("[]") {
-        let function = if parameter_type.ends_with("][]") {
-            tokenize
-        } else {
-            solidity_lookup
+/// Splits an array or tuple value such as `[1, [2, 3], "a, b"]` or `(1, abcd)` into its
+/// elements. Elements are separated by commas, or by whitespace when the list has no commas
+/// (e.g. `[10 12][13 42]`). Strings containing separators must be quoted.
+fn split_list(list: &str) -> Result<Vec<Element>> {
+    let list = list.trim();
+    let close = match list.as_bytes().first() {
+        Some(b'[') => b']',
+        Some(b'(') => b')',
+        _ => return Err(invalid_list(list, 0, "expected [ or (")),
+    };
+    let end = skip_group(list, 0)? - 1;
+    if list.as_bytes()[end] != close {
+        return Err(invalid_list(list, end, "mismatched bracket"));
+    }
+    if end != list.len() - 1 {
+        return Err(invalid_list(list, end + 1, "unexpected character"));
+    }
+
+    // Whitespace only separates elements when there are no top-level commas
+    let mut i = 1;
+    let mut has_commas = false;
+    while i < end {
+        match list.as_bytes()[i] {
+            b'"' => i = read_quoted(list, i)?.1,
+            b'[' | b'(' => i = skip_group(list, i)?,
+            b',' => {
+                has_commas = true;
+                break;
+            }
+            _ => i += 1,
+        }
+    }
+
+    let mut elements = vec![];
+    let mut after_comma = false;
+    let mut i = 1;
+    loop {
+        while i < end && list.as_bytes()[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i == end {
+            if after_comma {
+                return Err(invalid_list(list, i, "expected a value"));
+            }
+            break;
+        }
+
+        let element = match list.as_bytes()[i] {
+            b'"' => {
+                let (value, next) = read_quoted(list, i)?;
+                i = next;
+                Element::Quoted(value)
+            }
+            b'[' | b'(' => {
+                let start = i;
+                i = skip_group(list, i)?;
+                Element::Raw(list[start..i].to_string())
+            }
+            b',' => return Err(invalid_list(list, i, "expected a value")),
+            _ => {
+                let start = i;
+                while i < end {
+                    match list.as_bytes()[i] {
+                        b',' => break,
+                        c if c.is_ascii_whitespace() && !has_commas => break,
+                        b'"' => i = read_quoted(list, i)?.1,
+                        b'[' | b'(' => i = skip_group(list, i)?,
+                        _ => i += 1,
+                    }
+                }
+                Element::Raw(list[start..i].trim_end().to_string())
+            }
         };
+        elements.push(element);
+
+        while i < end && list.as_bytes()[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        after_comma = i < end && list.as_bytes()[i] == b',';
+        if after_comma {
+            i += 1;
+        } else if i < end && has_commas {
+            return Err(invalid_list(list, i, "expected ,"));
+        }
+    }
+    Ok(elements)
+}
+
+/// Element type and size of a fixed array type such as `uint256[2][10]`.
+fn fixed_array_type(parameter_type: &str) -> Result<(&str, usize)> {
+    let unsupported = || BridgeError::UnsupportedType(parameter_type.to_string());
+    let open = parameter_type.rfind('[').ok_or_else(unsupported)?;
+    let size = parameter_type[open + 1..parameter_type.len() - 1]
+        .parse::<usize>()
+        .map_err(|_| unsupported())?;
+    Ok((&parameter_type[..open], size))
+}
+
+fn check_length(parameter_type: &str, expected: usize, actual: usize, path: &str) -> Result<()> {
+    if actual != expected {
+        return Err(BridgeError::LengthMismatch {
+            kind: parameter_type.to_string(),
+            expected,
+            actual,
+        }
+        .at(path));
+    }
+    Ok(())
+}
+
+fn tokenize_element(parameter_type: &str, element: Element, path: &str) -> Result<Token> {
+    match element {
+        Element::Quoted(value)
+            if parameter_type.ends_with(']') || parameter_type.starts_with('(') =>
+        {
+            Err(BridgeError::TypeMismatch {
+                kind: parameter_type.to_string(),
+                value: format!("\"{}\"", value),
+            }
+            .at(path))
+        }
+        Element::Quoted(value) => {
+            solidity_lookup(parameter_type, &value).map_err(|error| error.at(path))
+        }
+        Element::Raw(value) => tokenize(parameter_type, &value, path),
+    }
+}
 
-        Token::Array(
-            group_array(parameter_value)
+/// Tokenizes `parameter_value` as `parameter_type`, `path` locates the value in the
+/// parameters for error messages, e.g. `parameters[1][0].2`.
+fn tokenize(parameter_type: &str, parameter_value: &str, path: &str) -> Result<Token> {
+    let split = |value| split_list(value).map_err(|error| error.at(path));
+    if let Some(element_type) = parameter_type.strip_suffix("[]") {
+        Ok(Token::Array(
+            split(parameter_value)?
                 .into_iter()
-                .map(|v| (function)(&parameter_type[0..parameter_type.len() - 2], &v))
-                .collect::<Vec<_>>(),
-        )
+                .enumerate()
+                .map(|(i, v)| tokenize_element(element_type, v, &format!("{}[{}]", path, i)))
+                .collect::<Result<Vec<_>>>()?,
+        ))
     } else if parameter_type.ends_with(']') {
-        let function =
-            if &parameter_type[parameter_type.len() - 4..parameter_type.len() - 2] == "][" {
-                tokenize
-            } else {
-                solidity_lookup
-            };
+        let (element_type, size) = fixed_array_type(parameter_type)?;
+        let values = split(parameter_value)?;
+        check_length(parameter_type, size, values.len(), path)?;
+
+        Ok(Token::FixedArray(
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(i, v)| tokenize_element(element_type, v, &format!("{}[{}]", path, i)))
+                .collect::<Result<Vec<_>>>()?,
+        ))
+    } else if let Some(components) = parameter_type
+        .strip_prefix('(')
+        .and_then(|inner| inner.strip_suffix(')'))
+    {
+        let components = split_top_level(components);
+        let values = split(parameter_value)?;
+        check_length(parameter_type, components.len(), values.len(), path)?;
+
+        Ok(Token::Tuple(
+            components
+                .into_iter()
+                .zip(values)
+                .enumerate()
+                .map(|(i, (component, value))| {
+                    tokenize_element(component.trim(), value, &format!("{}.{}", path, i))
+                })
+                .collect::<Result<Vec<_>>>()?,
+        ))
+    } else {
+        solidity_lookup(parameter_type, parameter_value).map_err(|error| error.at(path))
+    }
+}
+
+/// Splits a function signature such as `name(uint256,(address,bool)[])` into its name and
+/// parameters.
+fn split_signature(function: &str) -> Result<(&str, Vec<&str>)> {
+    let invalid = || BridgeError::InvalidSignature(function.to_string());
+    let open = function.find('(').ok_or_else(invalid)?;
+    let close = function
+        .rfind(')')
+        .filter(|close| *close > open)
+        .ok_or_else(invalid)?;
+
+    let inner = &function[open + 1..close];
+    let parameters = if inner.trim().is_empty() {
+        vec![]
+    } else {
+        split_top_level(inner).into_iter().map(str::trim).collect()
+    };
+    Ok((function[..open].trim(), parameters))
+}
+
+/// A parameter of a signature, with its canonical type and its tuple components, if any.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Parameter {
+    pub name: Option<String>,
+    /// Canonical type as used by solc for selectors, e.g. `(uint256,address)[]`
+    pub kind: String,
+    /// Components of the (possibly array of) tuple
+    pub components: Vec<Parameter>,
+}
 
-        Token::FixedArray(
-            group_array(parameter_value)
+/// Parses a parameter such as `(uint amount, address to)[] memory orders`, keeping its name
+/// and the names of its tuple components, and dropping its data location.
+pub(crate) fn parse_parameter(parameter: &str) -> Result<Parameter> {
+    let parameter = parameter.trim();
+    let parameter = parameter.strip_prefix("tuple").unwrap_or(parameter);
+
+    let (mut kind, components, rest) = if parameter.starts_with('(') {
+        let mut depth: usize = 0;
+        let close = parameter
+            .char_indices()
+            .find(|(_, c)| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            })
+            .map(|(i, _)| i)
+            .ok_or_else(|| BridgeError::UnsupportedType(parameter.to_string()))?;
+        let components = &parameter[1..close];
+        let components = if components.trim().is_empty() {
+            vec![]
+        } else {
+            split_top_level(components)
                 .into_iter()
-                .map(|v| (function)(&parameter_type[0..parameter_type.len() - 3], &v))
-                .collect::<Vec<_>>(),
+                .map(parse_parameter)
+                .collect::<Result<Vec<_>>>()?
+        };
+        let kinds = components
+            .iter()
+            .map(|component| component.kind.as_str())
+            .collect::<Vec<_>>();
+        (
+            format!("({})", kinds.join(",")),
+            components,
+            &parameter[close + 1..],
         )
     } else {
-        solidity_lookup(parameter_type, parameter_value)
+        let end = parameter
+            .find(|c: char| c.is_whitespace() || c == '[')
+            .unwrap_or(parameter.len());
+        let kind = match &parameter[..end] {
+            "uint" => "uint256",
+            "int" => "int256",
+            "byte" => "bytes1",
+            kind => kind,
+        };
+        (kind.to_string(), vec![], &parameter[end..])
+    };
+
+    // Array dimensions, possibly separated by whitespace
+    let mut rest = rest.trim_start();
+    while let Some(dimension) = rest.strip_prefix('[') {
+        let close = dimension
+            .find(']')
+            .ok_or_else(|| BridgeError::UnsupportedType(parameter.to_string()))?;
+        kind.push('[');
+        kind.push_str(dimension[..close].trim());
+        kind.push(']');
+        rest = dimension[close + 1..].trim_start();
     }
+
+    // Anything left is the data location and/or the name
+    let name = rest
+        .split_whitespace()
+        .rev()
+        .find(|word| !matches!(*word, "memory" | "calldata" | "storage"))
+        .map(Into::into);
+
+    Ok(Parameter {
+        name,
+        kind,
+        components,
+    })
 }
 
-pub(crate) fn solidity_function(function: &str, values: &[String]) -> Vec<u8> {
-    let parameters = function
-        .split(['(', ')', ',', ' '])
-        .skip(1)
-        .filter(|c| !c.is_empty())
-        .collect::<Vec<_>>();
+/// Canonical form of a parameter as used by solc for selectors, dropping its name and data
+/// location, e.g. `(uint amount, address to)[] memory orders` becomes `(uint256,address)[]`.
+pub(crate) fn canonical_type(parameter: &str) -> Result<String> {
+    Ok(parse_parameter(parameter)?.kind)
+}
 
-    assert_eq!(
-        parameters.len(),
-        values.len(),
-        "Number of parameters don't match"
-    );
+/// Canonical form of a function signature, e.g. `approve(address spender, uint amount)`
+/// becomes `approve(address,uint256)`.
+pub(crate) fn canonical_signature(function: &str) -> Result<String> {
+    let (name, parameters) = split_signature(function)?;
+    let parameters = parameters
+        .into_iter()
+        .map(canonical_type)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(format!("{}({})", name, parameters.join(",")))
+}
+
+/// Encodes a call to `function`, tokenizing each of its parameters with `tokenize_parameter`.
+fn encode_function(
+    function: &str,
+    values_len: usize,
+    tokenize_parameter: impl Fn(usize, &Parameter) -> Result<Token>,
+) -> Result<Vec<u8>> {
+    let (signature, tokens) = tokenize_function(function, values_len, tokenize_parameter)?;
+    build_input(&signature, &tokens)
+}
+
+/// Tokenizes the parameters of `function`, returning them with its signature without names.
+fn tokenize_function(
+    function: &str,
+    values_len: usize,
+    tokenize_parameter: impl Fn(usize, &Parameter) -> Result<Token>,
+) -> Result<(String, Vec<Token>)> {
+    let (name, parameters) = split_signature(function)?;
+    let parameters = parameters
+        .into_iter()
+        .map(parse_parameter)
+        .collect::<Result<Vec<_>>>()?;
+
+    if parameters.len() != values_len {
+        return Err(BridgeError::ParameterCount {
+            expected: parameters.len(),
+            actual: values_len,
+        });
+    }
 
     let parameters_token = parameters
         .iter()
         .enumerate()
         .map(|(i, parameter)| {
-            // Check the numerical part is correct
-            if let Some(numerical) = get_numerical_part(parameter) {
-                numeric_sanity_check(parameter, numerical);
-            }
+            // Check the type, including the numerical parts, is correct
+            param_type(&parameter.kind).map_err(|error| error.at(&parameter_path(i)))?;
 
-            tokenize(parameter, &values[i])
+            tokenize_parameter(i, parameter)
         })
+        .collect::<Result<Vec<_>>>()?;
+
+    let kinds = parameters
+        .iter()
+        .map(|parameter| parameter.kind.as_str())
         .collect::<Vec<_>>();
+    Ok((format!("{}({})", name, kinds.join(",")), parameters_token))
+}
+
+fn parameter_path(index: usize) -> String {
+    format!("parameters[{}]", index)
+}
+
+/// Encodes a call to `function` with `values` given as strings, e.g. `["0x...", "[1, 2]"]`.
+pub(crate) fn solidity_function(function: &str, values: &[impl AsRef<str>]) -> Result<Vec<u8>> {
+    encode_function(function, values.len(), |i, parameter| {
+        tokenize(&parameter.kind, values[i].as_ref(), &parameter_path(i))
+    })
+}
+
+/// Init code of a deployment: the hex `bytecode` followed by the arguments of `constructor`
+/// (e.g. `"constructor(string name, uint8 decimals)"`), which are encoded without a selector.
+pub(crate) fn deploy_input(
+    bytecode: &str,
+    constructor: Option<&str>,
+    values: &[impl AsRef<str>],
+) -> Result<Vec<u8>> {
+    let bytecode = hex::decode(strip_hex_prefix(bytecode.trim()))
+        .map_err(|e| BridgeError::invalid_value("bytes", "the bytecode", e).at("bytecode"))?;
+    let (_, tokens) = tokenize_function(
+        constructor.unwrap_or("constructor()"),
+        values.len(),
+        |i, parameter| tokenize(&parameter.kind, values[i].as_ref(), &parameter_path(i)),
+    )?;
+    Ok([bytecode, ethabi::encode(&tokens)].concat())
+}
+
+/// Same as `solidity_function`, with JSON values: arrays as JSON arrays, tuples as JSON arrays
+/// or objects keyed by the component names of the signature, numbers as JSON numbers or strings
+/// and booleans as JSON booleans.
+pub(crate) fn solidity_function_json(function: &str, values: &[Value]) -> Result<Vec<u8>> {
+    encode_function(function, values.len(), |i, parameter| {
+        tokenize_json(
+            &parameter.kind,
+            &parameter.components,
+            &values[i],
+            &parameter_path(i),
+        )
+    })
+}
+
+fn json_type_mismatch(parameter_type: &str, value: &Value, path: &str) -> BridgeError {
+    BridgeError::TypeMismatch {
+        kind: parameter_type.to_string(),
+        value: value.to_string(),
+    }
+    .at(path)
+}
+
+fn json_array<'a>(parameter_type: &str, value: &'a Value, path: &str) -> Result<&'a Vec<Value>> {
+    value
+        .as_array()
+        .ok_or_else(|| json_type_mismatch(parameter_type, value, path))
+}
 
-    build_input(function, &parameters_token)
+/// JSON counterpart of `tokenize`, `components` are the tuple components of `parameter_type`.
+fn tokenize_json(
+    parameter_type: &str,
+    components: &[Parameter],
+    value: &Value,
+    path: &str,
+) -> Result<Token> {
+    if let Some(element_type) = parameter_type.strip_suffix("[]") {
+        Ok(Token::Array(
+            json_array(parameter_type, value, path)?
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    tokenize_json(element_type, components, v, &format!("{}[{}]", path, i))
+                })
+                .collect::<Result<Vec<_>>>()?,
+        ))
+    } else if parameter_type.ends_with(']') {
+        let (element_type, size) = fixed_array_type(parameter_type)?;
+        let values = json_array(parameter_type, value, path)?;
+        check_length(parameter_type, size, values.len(), path)?;
+
+        Ok(Token::FixedArray(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    tokenize_json(element_type, components, v, &format!("{}[{}]", path, i))
+                })
+                .collect::<Result<Vec<_>>>()?,
+        ))
+    } else if parameter_type.starts_with('(') {
+        let values = match value {
+            Value::Array(values) => values.iter().collect::<Vec<_>>(),
+            Value::Object(object) => components
+                .iter()
+                .map(|component| {
+                    let name = component.name.as_ref().ok_or_else(|| {
+                        BridgeError::UnnamedComponents(parameter_type.to_string()).at(path)
+                    })?;
+                    object.get(name).ok_or_else(|| {
+                        BridgeError::MissingComponent {
+                            name: name.clone(),
+                            value: value.to_string(),
+                        }
+                        .at(path)
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => return Err(json_type_mismatch(parameter_type, value, path)),
+        };
+        check_length(parameter_type, components.len(), values.len(), path)?;
+
+        Ok(Token::Tuple(
+            components
+                .iter()
+                .zip(values)
+                .enumerate()
+                .map(|(i, (component, value))| {
+                    tokenize_json(
+                        &component.kind,
+                        &component.components,
+                        value,
+                        &format!("{}.{}", path, i),
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?,
+        ))
+    } else {
+        match value {
+            Value::String(value) => solidity_lookup(parameter_type, value),
+            Value::Bool(value) if parameter_type == "bool" => Ok(Token::Bool(*value)),
+            // Big numbers lose precision as JSON numbers, so only exact integers are accepted
+            Value::Number(number)
+                if (number.is_u64() || number.is_i64()) && parameter_type.contains("int") =>
+            {
+                solidity_lookup(parameter_type, &number.to_string())
+            }
+            _ => Err(json_type_mismatch(parameter_type, value, path)),
+        }
+        .map_err(|error| error.at(path))
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::UnwrapOrPanic;
     use crate::utils::{build_input, solidity_function};
     use ethabi::Token;
+    use near_sdk::serde_json::json;
+
+    fn tokenize(parameter_type: &str, parameter_value: &str) -> Token {
+        super::tokenize(parameter_type, parameter_value, "value").unwrap_or_panic()
+    }
+
+    fn raw(values: &[&str]) -> Vec<Element> {
+        values.iter().map(|v| Element::Raw(v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_split_list() {
+        assert_eq!(
+            split_list("[10, 12, 13, 42]").unwrap(),
+            raw(&["10", "12", "13", "42"])
+        );
+        assert_eq!(
+            split_list("[10 12 13 42]").unwrap(),
+            raw(&["10", "12", "13", "42"])
+        );
+        assert_eq!(
+            split_list("[[10, 12],[13, 42]]").unwrap(),
+            raw(&["[10, 12]", "[13, 42]"])
+        );
+        assert_eq!(
+            split_list("[[10 12][13 42]]").unwrap(),
+            raw(&["[10 12]", "[13 42]"])
+        );
+        assert_eq!(
+            split_list("[[[10, 1], [12, 2]],[[13, 3], [42, 4]]]").unwrap(),
+            raw(&["[[10, 1], [12, 2]]", "[[13, 3], [42, 4]]"])
+        );
+        assert_eq!(
+            split_list("[[[10 1] [12 2]][[13 3] [42 4]]]").unwrap(),
+            raw(&["[[10 1] [12 2]]", "[[13 3] [42 4]]"])
+        );
+        assert_eq!(split_list("[]").unwrap(), vec![]);
+        assert_eq!(split_list(" [ ] ").unwrap(), vec![]);
+        assert_eq!(
+            split_list("[ 1.5 ether , 2 gwei ]").unwrap(),
+            raw(&["1.5 ether", "2 gwei"])
+        );
+        assert_eq!(
+            split_list("(1, [], (true, abcd))").unwrap(),
+            raw(&["1", "[]", "(true, abcd)"])
+        );
+    }
 
     #[test]
-    fn test_group_array() {
+    fn test_split_list_quoted() {
+        assert_eq!(
+            split_list(r#"["hello, world", "a b", "", "[x]"]"#).unwrap(),
+            vec![
+                Element::Quoted("hello, world".to_string()),
+                Element::Quoted("a b".to_string()),
+                Element::Quoted("".to_string()),
+                Element::Quoted("[x]".to_string()),
+            ]
+        );
         assert_eq!(
-            group_array("[10, 12, 13, 42]"),
-            vec!["10", "12", "13", "42"]
+            split_list(r#"["say \"hi\"\n", "\u00e9\\"]"#).unwrap(),
+            vec![
+                Element::Quoted("say \"hi\"\n".to_string()),
+                Element::Quoted("\u{e9}\\".to_string()),
+            ]
         );
-        assert_eq!(group_array("[10 12 13 42]"), vec!["10", "12", "13", "42"]);
         assert_eq!(
-            group_array("[[10, 12],[13, 42]]"),
-            vec!["[10, 12]", "[13, 42]"]
+            split_list(r#"[["a]", "b"], []]"#).unwrap(),
+            raw(&[r#"["a]", "b"]"#, "[]"])
         );
-        assert_eq!(group_array("[[10 12][13 42]]"), vec!["[10 12]", "[13 42]"]);
+    }
+
+    #[test]
+    fn string_array() {
+        let function = "name(string[],string[][])";
+
+        let correct_input = build_input(
+            function,
+            &[
+                Token::Array(vec![
+                    Token::String("hello, world".to_string()),
+                    Token::String("a b".to_string()),
+                    Token::String("".to_string()),
+                ]),
+                Token::Array(vec![
+                    Token::Array(vec![]),
+                    Token::Array(vec![Token::String("]".to_string())]),
+                ]),
+            ],
+        )
+        .unwrap();
+
+        let input = solidity_function(
+            function,
+            &[
+                r#"["hello, world", "a b", ""]"#.to_string(),
+                r#"[[], ["]"]]"#.to_string(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(input, correct_input);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid input parameter: unterminated string at position 4 in [1, \"a]"
+    )]
+    fn unterminated_string() {
+        split_list(r#"[1, "a]"#).unwrap_or_panic();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid input parameter: unbalanced bracket at position 0 in [1, [2]"
+    )]
+    fn unbalanced_bracket() {
+        split_list("[1, [2]").unwrap_or_panic();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid input parameter: expected a value at position 4 in [1, , 2]"
+    )]
+    fn missing_value() {
+        split_list("[1, , 2]").unwrap_or_panic();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid input parameter: expected , at position 8 in [1, \"a\" b]")]
+    fn missing_comma() {
+        split_list(r#"[1, "a" b]"#).unwrap_or_panic();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid input parameter: expected [ or ( at position 0 in 5")]
+    fn not_a_list() {
+        tokenize("uint256[]", "5");
+    }
+
+    #[test]
+    fn test_param_type() {
+        assert_eq!(param_type("uint").unwrap(), ParamType::Uint(256));
+        assert_eq!(param_type("int64").unwrap(), ParamType::Int(64));
+        assert_eq!(param_type("bytes32").unwrap(), ParamType::FixedBytes(32));
         assert_eq!(
-            group_array("[[[10, 1], [12, 2]],[[13, 3], [42, 4]]]"),
-            vec!["[[10, 1], [12, 2]]", "[[13, 3], [42, 4]]"]
+            param_type("address[2][]").unwrap(),
+            ParamType::Array(Box::new(ParamType::FixedArray(
+                Box::new(ParamType::Address),
+                2
+            )))
         );
         assert_eq!(
-            group_array("[[[10 1] [12 2]][[13 3] [42 4]]]"),
-            vec!["[[10 1] [12 2]]", "[[13 3] [42 4]]"]
+            param_type("(bool,(string,bytes)[])").unwrap(),
+            ParamType::Tuple(vec![
+                ParamType::Bool,
+                ParamType::Array(Box::new(ParamType::Tuple(vec![
+                    ParamType::String,
+                    ParamType::Bytes
+                ])))
+            ])
         );
     }
 
+    #[test]
+    #[should_panic(expected = "Unsupported format uintx")]
+    fn unsupported_param_type() {
+        param_type("uintx").unwrap_or_panic();
+    }
+
     #[test]
     fn test_tokenize_array() {
         assert_eq!(
@@ -300,41 +1269,22 @@ mod tests {
             ]),
         );
         assert_eq!(
-            tokenize(
-                "uint256[2][2][2]",
-                "[[[1], [2], [3], [4]], [[4], [3], [2], [1]]]"
-            ),
+            tokenize("uint256[2][2][2]", "[[[1, 2], [3, 4]], [[4, 3], [2, 1]]]"),
             Token::FixedArray(vec![
                 Token::FixedArray(vec![
-                    Token::FixedArray(vec![Token::Uint(
-                        U256::from_dec_str("1").expect("valid uint256")
-                    ),]),
-                    Token::FixedArray(vec![Token::Uint(
-                        U256::from_dec_str("2").expect("valid uint256")
-                    ),]),
-                    Token::FixedArray(vec![Token::Uint(
-                        U256::from_dec_str("3").expect("valid uint256")
-                    ),]),
-                    Token::FixedArray(vec![Token::Uint(
-                        U256::from_dec_str("4").expect("valid uint256")
-                    ),]),
+                    Token::FixedArray(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+                    Token::FixedArray(vec![Token::Uint(3.into()), Token::Uint(4.into())])
                 ]),
                 Token::FixedArray(vec![
-                    Token::FixedArray(vec![Token::Uint(
-                        U256::from_dec_str("4").expect("valid uint256")
-                    ),]),
-                    Token::FixedArray(vec![Token::Uint(
-                        U256::from_dec_str("3").expect("valid uint256")
-                    ),]),
-                    Token::FixedArray(vec![Token::Uint(
-                        U256::from_dec_str("2").expect("valid uint256")
-                    ),]),
-                    Token::FixedArray(vec![Token::Uint(
-                        U256::from_dec_str("1").expect("valid uint256")
-                    ),]),
-                ]),
+                    Token::FixedArray(vec![Token::Uint(4.into()), Token::Uint(3.into())]),
+                    Token::FixedArray(vec![Token::Uint(2.into()), Token::Uint(1.into())])
+                ])
             ]),
         );
+        assert_eq!(
+            tokenize("uint8[10]", "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]"),
+            Token::FixedArray((0..10).map(|i| Token::Uint(i.into())).collect()),
+        );
     }
 
     #[test]
@@ -354,7 +1304,7 @@ mod tests {
         );
         assert_eq!(
             tokenize(
-                "uint256[2][][2]",
+                "uint256[1][][2]",
                 "[[[1], [2], [3], [4]], [[4], [3], [2], [1]]]"
             ),
             Token::FixedArray(vec![
@@ -390,13 +1340,40 @@ mod tests {
         );
     }
 
+    #[test]
+    #[should_panic(expected = "value[1]: Expected 2 elements for uint256[2], got 3")]
+    fn test_tokenize_fixed_array_length() {
+        tokenize("uint256[2][2]", "[[1, 2], [3, 4, 5]]");
+    }
+
+    #[test]
+    #[should_panic(expected = "parameters[1].1: Expected 3 elements for uint256[3], got 2")]
+    fn test_fixed_array_length_path() {
+        solidity_function(
+            "f(bool,(bool,uint256[3]))",
+            &["true".to_string(), "(true, [1, 2])".to_string()],
+        )
+        .unwrap_or_panic();
+    }
+
+    #[test]
+    #[should_panic(expected = "parameters[0]: Expected 2 elements for address[2], got 1")]
+    fn test_json_fixed_array_length() {
+        solidity_function_json(
+            "f(address[2])",
+            &[json!([format!("0x{}", "ab".repeat(20))])],
+        )
+        .unwrap_or_panic();
+    }
+
     #[test]
     #[should_panic(expected = "Invalid input parameter: OddLength")]
     fn wrong_address_odd() {
         solidity_function(
             "name(address,address)",
             &[String::from("something"), String::from("something")],
-        );
+        )
+        .unwrap_or_panic();
     }
 
     #[test]
@@ -405,7 +1382,8 @@ mod tests {
         solidity_function(
             "name(address,address)",
             &[String::from("somethings"), String::from("somethings")],
-        );
+        )
+        .unwrap_or_panic();
     }
 
     #[test]
@@ -414,7 +1392,8 @@ mod tests {
         solidity_function(
             "name(addrress,address)",
             &[String::from("somethings"), String::from("somethings")],
-        );
+        )
+        .unwrap_or_panic();
     }
 
     #[test]
@@ -423,7 +1402,8 @@ mod tests {
         solidity_function(
             "name(address,address,address)",
             &[String::from("somethings"), String::from("somethings")],
-        );
+        )
+        .unwrap_or_panic();
     }
 
     #[test]
@@ -436,30 +1416,47 @@ mod tests {
                 String::from("somethings"),
                 String::from("somethings"),
             ],
-        );
+        )
+        .unwrap_or_panic();
     }
 
     #[test]
     #[should_panic(expected = "Invalid input parameter: InvalidCharacter")]
     fn invalid_uint256() {
-        solidity_function("name(uint256)", &[String::from("somethings")]);
+        solidity_function("name(uint256)", &[String::from("somethings")]).unwrap_or_panic();
     }
 
     #[test]
     fn valid_uint256() {
-        solidity_function("name(uint256)", &[String::from("589")]);
+        solidity_function("name(uint256)", &[String::from("589")]).unwrap();
     }
 
     #[test]
     #[should_panic(expected = "The numerical part in uint257 is greater than 256")]
     fn invalid_uint257() {
-        solidity_function("name(uint257)", &[String::from("589")]);
+        solidity_function("name(uint257)", &[String::from("589")]).unwrap_or_panic();
     }
 
     #[test]
     #[should_panic(expected = "The numerical part in uint250 is not divisible by 8")]
     fn invalid_uint250() {
-        solidity_function("name(uint250)", &[String::from("589")]);
+        solidity_function("name(uint250)", &[String::from("589")]).unwrap_or_panic();
+    }
+
+    #[test]
+    fn invalid_int0() {
+        for kind in ["int0", "uint0"] {
+            assert_eq!(
+                solidity_function(&format!("name({})", kind), &[String::from("1")]),
+                Err(BridgeError::AtParameter {
+                    path: "parameters[0]".to_string(),
+                    error: Box::new(BridgeError::InvalidTypeSize {
+                        kind: kind.to_string(),
+                        reason: "is lower than 8".to_string(),
+                    }),
+                })
+            );
+        }
     }
 
     #[test]
@@ -485,8 +1482,9 @@ mod tests {
                 Token::Address(Address::from(user_u820)),
                 Token::Address(Address::from(pool_u820)),
             ],
-        );
-        let input = solidity_function(function, &[user, pool]);
+        )
+        .unwrap();
+        let input = solidity_function(function, &[user, pool]).unwrap();
 
         assert_eq!(input, correct_input);
     }
@@ -501,9 +1499,10 @@ mod tests {
             &[Token::Uint(
                 U256::from_dec_str(value_uint).expect("valid uint256"),
             )],
-        );
+        )
+        .unwrap();
 
-        let input = solidity_function(function, &[value_uint.to_string()]);
+        let input = solidity_function(function, &[value_uint.to_string()]).unwrap();
 
         assert_eq!(input, correct_input);
     }
@@ -518,9 +1517,10 @@ mod tests {
             &[Token::Uint(
                 U256::from_dec_str(value_uint256).expect("valid uint256"),
             )],
-        );
+        )
+        .unwrap();
 
-        let input = solidity_function(function, &[value_uint256.to_string()]);
+        let input = solidity_function(function, &[value_uint256.to_string()]).unwrap();
 
         assert_eq!(input, correct_input);
     }
@@ -535,10 +1535,11 @@ mod tests {
             &[Token::Array(vec![Token::Uint(
                 U256::from_dec_str(value_uint256).expect("valid uint256"),
             )])],
-        );
+        )
+        .unwrap();
 
         let values = format!("[{}]", value_uint256.to_string());
-        let input = solidity_function(function, &[values]);
+        let input = solidity_function(function, &[values]).unwrap();
 
         assert_eq!(input, correct_input);
     }
@@ -559,7 +1560,8 @@ mod tests {
                 ]),
                 Token::Bool(value_3_bool.parse::<bool>().expect("valid boolean")),
             ],
-        );
+        )
+        .unwrap();
 
         // getScheduled(uint256[], bool), ["[123456789, 987654321], true"])
         let values = format!(
@@ -567,7 +1569,7 @@ mod tests {
             value_1_uint256.to_string(),
             value_2_uint256.to_string()
         );
-        let input = solidity_function(function, &[values, value_3_bool.to_string()]);
+        let input = solidity_function(function, &[values, value_3_bool.to_string()]).unwrap();
 
         assert_eq!(input, correct_input);
     }
@@ -577,9 +1579,9 @@ mod tests {
         let function = "getScheduled(bytes)";
         let value_bytes: &str = "13";
 
-        let correct_input = build_input(function, &[Token::Bytes(vec![0x13])]);
+        let correct_input = build_input(function, &[Token::Bytes(vec![0x13])]).unwrap();
 
-        let input = solidity_function(function, &[value_bytes.to_string()]);
+        let input = solidity_function(function, &[value_bytes.to_string()]).unwrap();
 
         assert_eq!(input, correct_input);
     }
@@ -587,18 +1589,23 @@ mod tests {
     #[test]
     fn simple_test_bytes_sized() {
         let function = "getScheduled(bytes10,bytes)";
-        let value_bytes_sized: &str = "13";
+        let value_bytes_sized: &str = "13000000000000000042";
         let value_bytes: &str = "20";
 
         let correct_input = build_input(
             function,
-            &[Token::FixedBytes(vec![0x13]), Token::Bytes(vec![0x20])],
-        );
+            &[
+                Token::FixedBytes(vec![0x13, 0, 0, 0, 0, 0, 0, 0, 0, 0x42]),
+                Token::Bytes(vec![0x20]),
+            ],
+        )
+        .unwrap();
 
         let input = solidity_function(
             function,
             &[value_bytes_sized.to_string(), value_bytes.to_string()],
-        );
+        )
+        .unwrap();
 
         assert_eq!(input, correct_input);
     }
@@ -606,13 +1613,13 @@ mod tests {
     #[test]
     #[should_panic(expected = "The numerical part in bytes33 is out of bounds [1, 32]")]
     fn incorrect_upper_bound_bytes_sized() {
-        solidity_function("name(bytes33)", &[String::from("10")]);
+        solidity_function("name(bytes33)", &[String::from("10")]).unwrap_or_panic();
     }
 
     #[test]
     #[should_panic(expected = "The numerical part in bytes0 is out of bounds [1, 32]")]
     fn incorrect_lower_bound_bytes_sized() {
-        solidity_function("name(bytes0)", &[String::from("10")]);
+        solidity_function("name(bytes0)", &[String::from("10")]).unwrap_or_panic();
     }
 
     #[test]
@@ -632,9 +1639,10 @@ mod tests {
                     Token::Uint(U256::from_dec_str("21").expect("valid uint256")),
                 ]),
             ])],
-        );
+        )
+        .unwrap();
 
-        let input = solidity_function(function, &[value_bytes.to_string()]);
+        let input = solidity_function(function, &[value_bytes.to_string()]).unwrap();
 
         assert_eq!(input, correct_input);
     }
@@ -650,9 +1658,10 @@ mod tests {
                 Token::Bytes(vec![0x13, 0x15]),
                 Token::Bytes(vec![0x34, 0x30]),
             ])],
-        );
+        )
+        .unwrap();
 
-        let input = solidity_function(function, &[value_bytes.to_string()]);
+        let input = solidity_function(function, &[value_bytes.to_string()]).unwrap();
 
         assert_eq!(input, correct_input);
     }
@@ -660,13 +1669,709 @@ mod tests {
     #[test]
     fn simple_sized_bytes() {
         let function = "getScheduled(bytes2)";
-        let value_bytes: &str = "13153430";
+        let value_bytes: &str = "1315";
+
+        let correct_input = build_input(function, &[Token::FixedBytes(vec![0x13, 0x15])]).unwrap();
+
+        let input = solidity_function(function, &[value_bytes.to_string()]).unwrap();
+
+        assert_eq!(input, correct_input);
+    }
+
+    #[test]
+    #[should_panic(expected = "The value 13153430 has 4 bytes, expected exactly 2 for bytes2")]
+    fn sized_bytes_too_long() {
+        solidity_function("getScheduled(bytes2)", &[String::from("13153430")]).unwrap_or_panic();
+    }
+
+    #[test]
+    #[should_panic(expected = "The value 0x13 has 1 bytes, expected exactly 32 for bytes32")]
+    fn sized_bytes_too_short() {
+        solidity_function("getScheduled(bytes32)", &[String::from("0x13")]).unwrap_or_panic();
+    }
+
+    #[test]
+    fn simple_tuple() {
+        let function = "TestInputParsTuples1((uint256,address))";
+        let address = "2b8496768299a9C8e8957589CfA8ea48fa4d5A42";
+
+        let correct_input = build_input(
+            function,
+            &[Token::Tuple(vec![
+                Token::Uint(U256::from(5)),
+                Token::Address(from_string_to_address(address).unwrap()),
+            ])],
+        )
+        .unwrap();
+
+        let input = solidity_function(function, &[format!("(5, {})", address)]).unwrap();
+        assert_eq!(input, correct_input);
+
+        let input = solidity_function(
+            "TestInputParsTuples1((uint256, address))",
+            &[format!("[5, {}]", address)],
+        )
+        .unwrap();
+        assert_eq!(input, correct_input);
+    }
+
+    #[test]
+    fn nested_tuple() {
+        let function = "name(uint256,(uint256,(bool,string)))";
+
+        let correct_input = build_input(
+            function,
+            &[
+                Token::Uint(U256::from(1)),
+                Token::Tuple(vec![
+                    Token::Uint(U256::from(2)),
+                    Token::Tuple(vec![Token::Bool(true), Token::String("hello".to_string())]),
+                ]),
+            ],
+        )
+        .unwrap();
+
+        let input = solidity_function(
+            function,
+            &["1".to_string(), "(2, (true, hello))".to_string()],
+        )
+        .unwrap();
+        assert_eq!(input, correct_input);
+    }
 
-        let correct_input =
-            build_input(function, &[Token::FixedBytes(vec![0x13, 0x15, 0x34, 0x30])]);
+    #[test]
+    fn array_of_tuples() {
+        let function = "TestInputParsTuples3((uint256,bytes[2],uint256[])[])";
 
-        let input = solidity_function(function, &[value_bytes.to_string()]);
+        let correct_input = build_input(
+            function,
+            &[Token::Array(vec![
+                Token::Tuple(vec![
+                    Token::Uint(U256::from(1)),
+                    Token::FixedArray(vec![
+                        Token::Bytes(vec![0x13, 0x15]),
+                        Token::Bytes(vec![0x34, 0x30]),
+                    ]),
+                    Token::Array(vec![Token::Uint(U256::from(1)), Token::Uint(U256::from(2))]),
+                ]),
+                Token::Tuple(vec![
+                    Token::Uint(U256::from(2)),
+                    Token::FixedArray(vec![Token::Bytes(vec![0xaa]), Token::Bytes(vec![0xbb])]),
+                    Token::Array(vec![]),
+                ]),
+            ])],
+        )
+        .unwrap();
 
+        let input = solidity_function(
+            function,
+            &["[(1, [1315, 3430], [1, 2]), (2, [aa, bb], [])]".to_string()],
+        )
+        .unwrap();
         assert_eq!(input, correct_input);
     }
+
+    #[test]
+    #[should_panic(expected = "Expected 2 elements for (uint256,address), got 1")]
+    fn tuple_elements_dont_match() {
+        solidity_function("name((uint256,address))", &["(5)".to_string()]).unwrap_or_panic();
+    }
+
+    #[test]
+    fn test_canonical_signature() {
+        assert_eq!(
+            canonical_signature("getScheduled(uint)").unwrap(),
+            "getScheduled(uint256)"
+        );
+        assert_eq!(
+            canonical_signature("getScheduled(uint256, bool)").unwrap(),
+            "getScheduled(uint256,bool)"
+        );
+        assert_eq!(
+            canonical_signature("approve(address spender, uint256 amount)").unwrap(),
+            "approve(address,uint256)"
+        );
+        assert_eq!(
+            canonical_signature("name(int[] memory values, uint [2] calldata pair)").unwrap(),
+            "name(int256[],uint256[2])"
+        );
+        assert_eq!(
+            canonical_signature(
+                "name( (uint amount, address to)[] memory orders, tuple(bool) flag )"
+            )
+            .unwrap(),
+            "name((uint256,address)[],(bool))"
+        );
+        assert_eq!(canonical_signature("name()").unwrap(), "name()");
+        assert_eq!(canonical_signature(" name ( ) ").unwrap(), "name()");
+    }
+
+    #[test]
+    fn test_get_selector() {
+        assert_eq!(
+            get_selector("approve(address,uint256)").unwrap(),
+            [0x09, 0x5e, 0xa7, 0xb3]
+        );
+        assert_eq!(
+            get_selector("approve(address spender, uint amount)").unwrap(),
+            [0x09, 0x5e, 0xa7, 0xb3]
+        );
+    }
+
+    #[test]
+    fn canonical_function_input() {
+        let value_uint: &str = "4294967295";
+        let correct_input = solidity_function(
+            "getScheduled(uint256,bool)",
+            &[value_uint.to_string(), "true".to_string()],
+        )
+        .unwrap();
+
+        assert_eq!(
+            solidity_function(
+                "getScheduled(uint, bool)",
+                &[value_uint.to_string(), "true".to_string()]
+            )
+            .unwrap(),
+            correct_input
+        );
+        assert_eq!(
+            solidity_function(
+                "getScheduled(uint256 amount, bool enabled)",
+                &[value_uint.to_string(), "true".to_string()]
+            )
+            .unwrap(),
+            correct_input
+        );
+    }
+
+    #[test]
+    fn signed_int() {
+        assert_eq!(tokenize("int256", "5"), Token::Int(U256::from(5)));
+        assert_eq!(tokenize("int256", "-5"), Token::Int(!U256::from(4)));
+        assert_eq!(tokenize("int8", "-128"), Token::Int(!U256::from(127)));
+        assert_eq!(tokenize("int8", "127"), Token::Int(U256::from(127)));
+        assert_eq!(tokenize("int256", "-1"), Token::Int(U256::max_value()),);
+
+        let function = "TestInputPars15(int256)";
+        let input = solidity_function(function, &["-5".to_string()]).unwrap();
+        assert_eq!(input[4..], [vec![0xff; 31], vec![0xfb]].concat()[..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The value -129 is out of range for int8")]
+    fn signed_int_lower_bound() {
+        solidity_function("name(int8)", &["-129".to_string()]).unwrap_or_panic();
+    }
+
+    #[test]
+    #[should_panic(expected = "The value 128 is out of range for int8")]
+    fn signed_int_upper_bound() {
+        solidity_function("name(int8)", &["128".to_string()]).unwrap_or_panic();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid input parameter: InvalidCharacter")]
+    fn negative_uint() {
+        solidity_function("name(uint256)", &["-1".to_string()]).unwrap_or_panic();
+    }
+
+    #[test]
+    fn uint_bounds() {
+        assert_eq!(tokenize("uint8", "255"), Token::Uint(U256::from(255)));
+        assert_eq!(
+            tokenize("uint256", &U256::max_value().to_string()),
+            Token::Uint(U256::max_value())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The value 256 is out of range for uint8")]
+    fn uint_upper_bound() {
+        solidity_function("name(uint8)", &["256".to_string()]).unwrap_or_panic();
+    }
+
+    #[test]
+    #[should_panic(expected = "is out of range for uint8")]
+    fn uint_upper_bound_large() {
+        solidity_function("name(uint8)", &[(U256::one() << 200).to_string()]).unwrap_or_panic();
+    }
+
+    #[test]
+    fn hex_and_prefixed_inputs() {
+        assert_eq!(tokenize("uint256", "0x5dc"), Token::Uint(U256::from(1500)));
+        assert_eq!(tokenize("uint256", "0X05DC"), Token::Uint(U256::from(1500)));
+        assert_eq!(tokenize("int256", "-0x10"), Token::Int(!U256::from(15)));
+        assert_eq!(
+            tokenize("address", "0x2b8496768299a9C8e8957589CfA8ea48fa4d5A42"),
+            tokenize("address", "2b8496768299a9C8e8957589CfA8ea48fa4d5A42")
+        );
+        assert_eq!(tokenize("bytes", "0x1315"), Token::Bytes(vec![0x13, 0x15]));
+        assert_eq!(
+            from_string_to_address("0x2b8496768299a9C8e8957589CfA8ea48fa4d5A42").unwrap(),
+            from_string_to_address("2b8496768299a9C8e8957589CfA8ea48fa4d5A42").unwrap()
+        );
+    }
+
+    #[test]
+    fn scientific_and_unit_inputs() {
+        let one_and_half_ether = U256::from(15) * U256::from(10).pow(U256::from(17));
+
+        assert_eq!(
+            tokenize("uint256", "1.5e18"),
+            Token::Uint(one_and_half_ether)
+        );
+        assert_eq!(
+            tokenize("uint256", "1.5 ether"),
+            Token::Uint(one_and_half_ether)
+        );
+        assert_eq!(
+            tokenize("uint256", "1.5ether"),
+            Token::Uint(one_and_half_ether)
+        );
+        assert_eq!(
+            tokenize("uint256", "1500000000 gwei"),
+            Token::Uint(one_and_half_ether)
+        );
+        assert_eq!(tokenize("uint256", "25 wei"), Token::Uint(U256::from(25)));
+        assert_eq!(tokenize("uint256", "2.50e1"), Token::Uint(U256::from(25)));
+        assert_eq!(tokenize("uint256", "1E3"), Token::Uint(U256::from(1000)));
+        assert_eq!(tokenize("int256", "-1.5e3"), Token::Int(!U256::from(1499)));
+        assert_eq!(tokenize("uint256", "1e-18 ether"), Token::Uint(U256::one()));
+    }
+
+    #[test]
+    #[should_panic(expected = "The value 1.5 wei is not an integer")]
+    fn fractional_wei() {
+        tokenize("uint256", "1.5 wei");
+    }
+
+    #[test]
+    #[should_panic(expected = "The value 1e-19 ether is not an integer")]
+    fn fractional_wei_scientific() {
+        tokenize("uint256", "1e-19 ether");
+    }
+
+    #[test]
+    #[should_panic(expected = "The value 1e300 is out of range for uint256")]
+    fn scientific_overflow() {
+        tokenize("uint256", "1e300");
+    }
+
+    #[test]
+    fn huge_exponents() {
+        assert_eq!(
+            parse_number("uint256", "1e-4294967296"),
+            Err(BridgeError::NotAnInteger("1e-4294967296".to_string()))
+        );
+        assert_eq!(
+            parse_number("uint256", "100e-4294967298"),
+            Err(BridgeError::NotAnInteger("100e-4294967298".to_string()))
+        );
+        assert_eq!(
+            parse_number("uint256", "0e-4294967296").unwrap(),
+            U256::zero()
+        );
+        assert_eq!(
+            parse_number("uint256", "1e9223372036854775807 ether"),
+            Err(out_of_range("uint256", "1e9223372036854775807 ether"))
+        );
+        assert_eq!(
+            parse_number("uint256", "1.5e-9223372036854775808"),
+            Err(BridgeError::NotAnInteger(
+                "1.5e-9223372036854775808".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn digitless_numbers() {
+        for value in ["", ".", "ether", "e18", ".e3", "0x", "0X"] {
+            assert_eq!(
+                parse_number("uint256", value),
+                Err(BridgeError::InvalidValue {
+                    kind: "uint256".to_string(),
+                    value: value.to_string(),
+                    reason: "no digits".to_string(),
+                })
+            );
+        }
+        assert!(matches!(
+            parse_int("int256", "-"),
+            Err(BridgeError::InvalidValue { reason, .. }) if reason == "no digits"
+        ));
+    }
+
+    #[test]
+    fn non_ascii_number() {
+        for value in ["€e-1", "1.5€", "١٢"] {
+            assert_eq!(
+                parse_number("uint256", value),
+                Err(BridgeError::invalid_value(
+                    "uint256",
+                    value,
+                    FromDecStrErr::InvalidCharacter
+                ))
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The value 1 ether is out of range for uint8")]
+    fn unit_out_of_range() {
+        tokenize("uint8", "1 ether");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid input parameter: InvalidCharacter")]
+    fn unknown_unit() {
+        tokenize("uint256", "1 finney");
+    }
+
+    #[test]
+    fn checksum_address() {
+        // Test vectors from EIP-55
+        for address in [
+            "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "fB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "dbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "D1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                to_checksum_address(&from_string_to_address(address).unwrap().0),
+                *address
+            );
+        }
+
+        // All lowercase and all uppercase skip the checksum
+        assert_eq!(
+            from_string_to_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap(),
+            from_string_to_address("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED").unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid address checksum for 5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD, expected 5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+    )]
+    fn invalid_checksum_address() {
+        solidity_function(
+            "transfer(address,uint256)",
+            &[
+                "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD".to_string(),
+                "1".to_string(),
+            ],
+        )
+        .unwrap_or_panic();
+    }
+
+    #[test]
+    fn function_type() {
+        let function = "subscribe(function)";
+        let address = "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let mut callback = hex::decode(address).expect("valid address");
+        callback.extend([0xa9, 0x05, 0x9c, 0xbb]);
+
+        let correct_input = build_input(function, &[Token::FixedBytes(callback)]).unwrap();
+        assert_eq!(correct_input.len(), 4 + 32);
+
+        assert_eq!(
+            solidity_function(function, &[format!("{}:a9059cbb", address)]).unwrap(),
+            correct_input
+        );
+        assert_eq!(
+            solidity_function(function, &[format!("0x{}:0xa9059cbb", address)]).unwrap(),
+            correct_input
+        );
+        assert_eq!(
+            solidity_function(
+                function,
+                &[format!("{}:transfer(address to, uint amount)", address)]
+            )
+            .unwrap(),
+            correct_input
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid input parameter: InvalidStringLength")]
+    fn function_type_invalid_selector() {
+        solidity_function(
+            "subscribe(function)",
+            &["5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed:a9059c".to_string()],
+        )
+        .unwrap_or_panic();
+    }
+
+    #[test]
+    #[should_panic(expected = "must be address:selector or address:signature")]
+    fn function_type_missing_selector() {
+        solidity_function(
+            "subscribe(function)",
+            &["5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string()],
+        )
+        .unwrap_or_panic();
+    }
+
+    #[test]
+    fn test_parse_parameter() {
+        assert_eq!(
+            parse_parameter("(uint amount, address to)[] memory orders").unwrap(),
+            Parameter {
+                name: Some("orders".to_string()),
+                kind: "(uint256,address)[]".to_string(),
+                components: vec![
+                    Parameter {
+                        name: Some("amount".to_string()),
+                        kind: "uint256".to_string(),
+                        components: vec![],
+                    },
+                    Parameter {
+                        name: Some("to".to_string()),
+                        kind: "address".to_string(),
+                        components: vec![],
+                    },
+                ],
+            }
+        );
+        assert_eq!(parse_parameter("bytes calldata").unwrap().name, None);
+    }
+
+    #[test]
+    fn json_parameters() {
+        let function = "getScheduled(uint256[][],bool,string[],int8)";
+        let values = vec![
+            json!([[13, "34"], [], ["0x2b"]]),
+            json!(true),
+            json!(["hello, world", "a b"]),
+            json!(-5),
+        ];
+
+        let correct_input = build_input(
+            function,
+            &[
+                Token::Array(vec![
+                    Token::Array(vec![
+                        Token::Uint(U256::from(13)),
+                        Token::Uint(U256::from(34)),
+                    ]),
+                    Token::Array(vec![]),
+                    Token::Array(vec![Token::Uint(U256::from(43))]),
+                ]),
+                Token::Bool(true),
+                Token::Array(vec![
+                    Token::String("hello, world".to_string()),
+                    Token::String("a b".to_string()),
+                ]),
+                Token::Int(!U256::from(4)),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            solidity_function_json(function, &values).unwrap(),
+            correct_input
+        );
+    }
+
+    #[test]
+    fn json_tuple_parameters() {
+        let function = "TestInputParsTuples2(uint256 par1, (uint256 tupleIn01, address tupleIn02) _data, uint256[2] par3)";
+        let address = "2b8496768299a9C8e8957589CfA8ea48fa4d5A42";
+
+        let correct_input = build_input(
+            "TestInputParsTuples2(uint256,(uint256,address),uint256[2])",
+            &[
+                Token::Uint(U256::from(1)),
+                Token::Tuple(vec![
+                    Token::Uint(U256::from(2)),
+                    Token::Address(from_string_to_address(address).unwrap()),
+                ]),
+                Token::FixedArray(vec![Token::Uint(U256::from(3)), Token::Uint(U256::from(4))]),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            solidity_function_json(
+                function,
+                &[
+                    json!(1),
+                    json!({"tupleIn02": address, "tupleIn01": "2"}),
+                    json!([3, 4])
+                ]
+            )
+            .unwrap(),
+            correct_input
+        );
+        assert_eq!(
+            solidity_function_json(
+                function,
+                &[json!("1"), json!([2, address]), json!([3, "4"])]
+            )
+            .unwrap(),
+            correct_input
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid input parameter: missing tupleIn02")]
+    fn json_tuple_missing_component() {
+        solidity_function_json(
+            "name((uint256 tupleIn01, address tupleIn02))",
+            &[json!({"tupleIn01": 2})],
+        )
+        .unwrap_or_panic();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid input parameter: 1.5 is not a valid uint256")]
+    fn json_float_number() {
+        solidity_function_json("name(uint256)", &[json!(1.5)]).unwrap_or_panic();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid input parameter: \"true\" is not a valid uint256[]")]
+    fn json_value_type_mismatch() {
+        solidity_function_json("name(uint256[])", &[json!("true")]).unwrap_or_panic();
+    }
+
+    #[test]
+    fn test_near_account_to_address() {
+        assert_eq!(
+            hex::encode(near_account_to_address("account.testnet")),
+            "6eba6ef721bd532ca8e5d5ec8faf7f0fa0dac474"
+        );
+    }
+
+    #[test]
+    fn test_deploy_input() {
+        assert_eq!(
+            deploy_input("0x6080", None, &[] as &[&str]).unwrap(),
+            vec![0x60, 0x80]
+        );
+        assert_eq!(
+            deploy_input(
+                "6080",
+                Some("constructor(string name, uint8 decimals)"),
+                &["Token", "18"]
+            )
+            .unwrap(),
+            [
+                vec![0x60, 0x80],
+                ethabi::encode(&[Token::String("Token".to_string()), Token::Uint(18.into())])
+            ]
+            .concat()
+        );
+        assert_eq!(
+            deploy_input("6080", None, &["1"]),
+            Err(BridgeError::ParameterCount {
+                expected: 0,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "bytecode: Invalid input parameter: OddLength in the bytecode")]
+    fn test_deploy_input_invalid_bytecode() {
+        deploy_input("0x608", None, &[] as &[&str]).unwrap_or_panic();
+    }
+
+    #[test]
+    fn test_create2_address() {
+        // Examples of EIP-1014
+        let init_code_hash = keccak(&[0x00]).0;
+        assert_eq!(
+            to_checksum_address(&create2_address(&[0; 20], &[0; 32], &init_code_hash)),
+            "4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38"
+        );
+        let factory = from_string_to_address("deadbeef00000000000000000000000000000000").unwrap();
+        assert_eq!(
+            to_checksum_address(&create2_address(&factory.0, &[0; 32], &init_code_hash)),
+            "B928f69Bb1D91Cd65274e3c79d8986362984fDA3"
+        );
+    }
+
+    #[test]
+    fn test_parse_bytes32() {
+        assert_eq!(
+            parse_bytes32(&format!("0x{}", "00".repeat(32))).unwrap(),
+            [0; 32]
+        );
+        assert_eq!(
+            parse_bytes32("0x01"),
+            Err(BridgeError::BytesLength {
+                kind: "bytes32".to_string(),
+                value: "0x01".to_string(),
+                expected: 32,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_wei() {
+        let mut wei = RawU256::default();
+        wei[31] = 0xe8;
+        wei[30] = 0x03;
+        assert_eq!(parse_wei("1000").unwrap(), wei);
+        assert_eq!(parse_wei("0x3e8").unwrap(), wei);
+        assert_eq!(parse_wei("1000 wei").unwrap(), wei);
+        assert_eq!(
+            U256::from_big_endian(&parse_wei("1.5 ether").unwrap()),
+            U256::from(15) * U256::exp10(17)
+        );
+        assert!(parse_wei("-1").is_err());
+    }
+
+    #[test]
+    fn test_bridge_errors() {
+        assert_eq!(
+            solidity_function("name(uint256,bool)", &["1".to_string()]),
+            Err(BridgeError::ParameterCount {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            solidity_function("name", &["1"]),
+            Err(BridgeError::InvalidSignature("name".to_string()))
+        );
+        assert_eq!(
+            solidity_function(
+                "name(bool,addrress)",
+                &["true".to_string(), "0".to_string()]
+            ),
+            Err(BridgeError::UnsupportedType("addrress".to_string()).at("parameters[1]"))
+        );
+        assert_eq!(
+            solidity_function(
+                "name(bool,uint8[])",
+                &["true".to_string(), "[1, 256]".to_string()]
+            ),
+            Err(BridgeError::OutOfRange {
+                kind: "uint8".to_string(),
+                value: "256".to_string()
+            }
+            .at("parameters[1][1]"))
+        );
+        assert_eq!(
+            from_string_to_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+            Err(BridgeError::InvalidChecksum {
+                address: "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD".to_string(),
+                expected: "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string()
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "parameters[0].1: Invalid input parameter: OddLength in 0x123 for address"
+    )]
+    fn bad_value_message() {
+        solidity_function("name((bool,address))", &["(true, 0x123)".to_string()]).unwrap_or_panic();
+    }
 }